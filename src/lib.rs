@@ -1,13 +1,84 @@
 use rand::{Rng, rngs::ThreadRng};
-use std::collections::VecDeque;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BTreeMap, BinaryHeap, HashMap, VecDeque};
 use std::fmt;
+use std::io::{self, Write};
+use std::iter::Sum;
+use std::ops::{Add, Index, RangeInclusive};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Weighted(pub u32);
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Unweighted(pub ());
+/// Edge weight that may be negative, for algorithms such as Bellman–Ford.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SignedWeighted(pub i64);
+
+impl Weighted {
+    pub fn weight(&self) -> u32 {
+        self.0
+    }
+}
+
+impl From<u32> for Weighted {
+    fn from(weight: u32) -> Self {
+        Weighted(weight)
+    }
+}
+
+impl From<Weighted> for u32 {
+    fn from(weight: Weighted) -> Self {
+        weight.0
+    }
+}
+
+/// Adds the underlying weights.
+///
+/// Panics if the sum does not fit in a `u32`, in release builds too, so an
+/// overflow is never silently wrapped; algorithms that must not panic report
+/// [`GraphError::DistanceOverflow`] instead.
+impl Add for Weighted {
+    type Output = Weighted;
+
+    fn add(self, other: Weighted) -> Weighted {
+        Weighted(
+            self.0
+                .checked_add(other.0)
+                .expect("Weighted addition overflowed"),
+        )
+    }
+}
+
+/// Sums the weights with [`Add`], so it panics on overflow as well.
+impl Sum for Weighted {
+    fn sum<I: Iterator<Item = Weighted>>(iter: I) -> Self {
+        iter.fold(Weighted(0), Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Weighted> for Weighted {
+    fn sum<I: Iterator<Item = &'a Weighted>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
 
+/// Errors returned by graph algorithms.
+///
+/// New variants may be added as the library grows, so the enum is
+/// `#[non_exhaustive]`: code matching on it outside this crate must include a
+/// wildcard `_` arm.
+///
+/// ```rust
+/// use edgewise::{Graph, GraphError, Unweighted};
+/// let g: Graph<Unweighted> = Graph::default();
+/// match g.bfs(0) {
+///     Ok(nodes) => println!("visited {nodes:?}"),
+///     Err(GraphError::OutOfBoundsNode { node }) => println!("no node {node}"),
+///     Err(_) => println!("another error"),
+/// }
+/// ```
 #[derive(Debug, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum GraphError {
     OutOfBoundsNode {
         node: u32,
@@ -15,11 +86,108 @@ pub enum GraphError {
     DistanceOverflow {
         node_from: u32,
         node_to: u32,
-        current_distance: u32,
-        edge_weight: u32,
+        current_distance: i64,
+        edge_weight: i64,
+    },
+    InvalidPermutation,
+    InvalidPartition,
+    /// A negative-weight cycle is reachable from the source; `node` is a node
+    /// whose distance could still be decreased.
+    NegativeCycle {
+        node: u32,
+    },
+    ZeroWeight {
+        node_from: u32,
+        node_to: u32,
+    },
+    NotConnected,
+    /// The graph is expected to store both half-edges of every undirected
+    /// edge, but its edges are inconsistent with that.
+    NotUndirected,
+    /// The graph has `count` odd-degree nodes, more than the `limit` that
+    /// [`Graph::route_inspection_length`] supports.
+    TooManyOddNodes {
+        count: usize,
+        limit: usize,
+    },
+    /// A sum of edge weights does not fit in a `u64`.
+    WeightOverflow,
+    CyclePresent,
+    /// A probability outside `[0.0, 1.0]`, or NaN.
+    InvalidProbability,
+    /// Two graphs that must share the same node set have different numbers
+    /// of nodes.
+    NodeCountMismatch {
+        expected: usize,
+        found: usize,
+    },
+    /// An edge has a negative weight, which Dijkstra-based algorithms do not
+    /// support; use [`Graph::bellman_ford`] instead.
+    NegativeEdge {
+        node_from: u32,
+        node_to: u32,
     },
 }
 
+/// Errors produced when reading a graph from a textual format.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// No problem line (`p sp N M`) precedes the edges.
+    MissingHeader,
+    /// A line (1-based) that could not be parsed.
+    InvalidLine { line: usize },
+    /// A line (1-based) refers to a node that is not declared in the header.
+    NodeOutOfRange { line: usize, node: u32 },
+    /// The header announced `expected` edges but `found` were read.
+    EdgeCountMismatch { expected: usize, found: usize },
+    /// The header on `line` declares more than [`Graph::MAX_DIMACS_NODES`]
+    /// nodes.
+    TooManyNodes { line: usize, nodes: u32 },
+}
+
+/// Shortest-path distances from a single source, as computed by
+/// [`Graph::dijkstra_result`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShortestPaths {
+    source: u32,
+    distances: Vec<Option<u32>>,
+}
+
+impl ShortestPaths {
+    pub fn source(&self) -> u32 {
+        self.source
+    }
+
+    /// Distance from the source to `node`, or `None` if `node` is unreachable
+    /// or out of bounds.
+    pub fn distance_to(&self, node: u32) -> Option<u32> {
+        self.distances.get(node as usize).copied().flatten()
+    }
+
+    pub fn is_reachable(&self, node: u32) -> bool {
+        self.distance_to(node).is_some()
+    }
+
+    /// Distances indexed by node, as returned by [`Graph::dijkstra`].
+    pub fn distances(&self) -> &[Option<u32>] {
+        &self.distances
+    }
+}
+
+/// Kind of a directed edge in a depth-first search forest, as reported by
+/// [`Graph::classify_edges`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeKind {
+    /// The edge discovered its target.
+    Tree,
+    /// The target is an ancestor of the source (self-loops included).
+    Back,
+    /// The target is a proper descendant reached earlier by another path.
+    Forward,
+    /// Any other edge: the target lies in an already finished subtree.
+    Cross,
+}
+
 /// A graph is represented as an adjacency list, which is internally
 /// modelled as a vector of vectors `Vec<Vec<(u32, W)>>`.
 /// Each index corresponds to a node, and each inner `Vec` stores
@@ -66,149 +234,3171 @@ impl<W> Graph<W> {
         Self { graph: g }
     }
 
-    // An iterator over the edges of the graph.
-    pub fn edges(&self) -> impl Iterator<Item = (u32, u32, &W)> + '_ {
-        self.graph.iter().enumerate().flat_map(|(u, v)| {
-            let x = u as u32; //safe as the number of nodes is capped by u32 anyway
-            v.iter().map(move |(y, w)| (x, *y, w))
-        })
+    pub fn node_count(&self) -> usize {
+        self.graph.len()
     }
 
-    pub fn bfs(&self, starting_node: u32) -> Result<Vec<u32>, GraphError> {
-        if (starting_node as usize) >= self.graph.len() {
-            return Err(GraphError::OutOfBoundsNode {
-                node: starting_node,
+    /// Appends a new node without edges and returns its identifier.
+    pub fn add_node(&mut self) -> u32 {
+        let node = self.graph.len();
+        assert!(
+            node < u32::MAX as usize,
+            "The number of nodes of the graph must fit in u32."
+        );
+        self.graph.push(Vec::new());
+        node as u32
+    }
+
+    /// Adds the directed edge `from -> to` with the given weight.
+    pub fn add_edge(&mut self, from: u32, to: u32, weight: W) -> Result<(), GraphError> {
+        self.check_node(from)?;
+        self.check_node(to)?;
+        self.graph[from as usize].push((to, weight));
+        Ok(())
+    }
+
+    /// Adds the directed edge `from -> to` unless some edge `from -> to`
+    /// already exists, whatever its weight. Returns whether the edge was
+    /// added.
+    pub fn add_edge_unique(&mut self, from: u32, to: u32, weight: W) -> Result<bool, GraphError> {
+        self.check_node(from)?;
+        self.check_node(to)?;
+        let edges = &mut self.graph[from as usize];
+        if edges.iter().any(|&(v, _)| v == to) {
+            return Ok(false);
+        }
+        edges.push((to, weight));
+        Ok(true)
+    }
+
+    /// New graph with the same edges, where each weight `w` is replaced by
+    /// `f(w)`.
+    pub fn map_weights<W2>(&self, f: impl Fn(&W) -> W2) -> Graph<W2> {
+        Graph::new(
+            self.graph
+                .iter()
+                .map(|edges| edges.iter().map(|(v, w)| (*v, f(w))).collect())
+                .collect(),
+        )
+    }
+
+    /// Reverses every edge in place. Edges are moved rather than cloned, so
+    /// no second copy of the weights is ever kept.
+    pub fn transpose_in_place(&mut self) {
+        let old = std::mem::take(&mut self.graph);
+        self.graph.resize_with(old.len(), Vec::new);
+        for (u, edges) in old.into_iter().enumerate() {
+            for (v, w) in edges {
+                self.graph[v as usize].push((u as u32, w));
+            }
+        }
+    }
+
+    /// Removes, in place, every edge `(from, to, weight)` for which `pred`
+    /// returns false.
+    pub fn retain_edges(&mut self, pred: impl Fn(u32, u32, &W) -> bool) {
+        for (u, edges) in self.graph.iter_mut().enumerate() {
+            edges.retain(|(v, w)| pred(u as u32, *v, w));
+        }
+    }
+
+    /// Deletes every edge leaving or entering `node`. The node itself stays as
+    /// an isolated placeholder, so the ids of the other nodes do not shift;
+    /// pass it to [`Graph::compact`] once all removals are done to drop it.
+    pub fn remove_node(&mut self, node: u32) -> Result<(), GraphError> {
+        self.check_node(node)?;
+        self.graph[node as usize].clear();
+        self.retain_edges(|_, v, _| v != node);
+        Ok(())
+    }
+
+    /// Drops the nodes listed in `removed`, such as those passed to
+    /// [`Graph::remove_node`], together with every edge touching them, and
+    /// renumbers the remaining nodes consecutively in their original order.
+    /// Returns the mapping from each old id to its new id, `None` for dropped
+    /// nodes. Nothing changes if `removed` is empty.
+    pub fn compact(&mut self, removed: &[u32]) -> Result<Vec<Option<u32>>, GraphError> {
+        let mut keep: Vec<bool> = vec![true; self.graph.len()];
+        for &node in removed {
+            self.check_node(node)?;
+            keep[node as usize] = false;
+        }
+        let mut new_id: Vec<Option<u32>> = vec![None; self.graph.len()];
+        let mut next = 0;
+        for (node, _) in keep.iter().enumerate().filter(|&(_, &k)| k) {
+            new_id[node] = Some(next);
+            next += 1;
+        }
+        if next as usize == self.graph.len() {
+            return Ok(new_id);
+        }
+        let old = std::mem::take(&mut self.graph);
+        self.graph = old
+            .into_iter()
+            .enumerate()
+            .filter(|&(u, _)| new_id[u].is_some())
+            .map(|(_, edges)| {
+                edges
+                    .into_iter()
+                    .filter_map(|(v, w)| new_id[v as usize].map(|v| (v, w)))
+                    .collect()
+            })
+            .collect();
+        Ok(new_id)
+    }
+
+    /// Releases the spare capacity left behind by incremental construction,
+    /// both in the node list and in every adjacency list.
+    pub fn shrink_to_fit(&mut self) {
+        self.graph.shrink_to_fit();
+        for edges in &mut self.graph {
+            edges.shrink_to_fit();
+        }
+    }
+
+    /// Read-only view of the underlying adjacency list.
+    ///
+    /// ```rust
+    /// use edgewise::{Graph, Weighted};
+    /// let g = Graph::new(vec![vec![(1, Weighted(4))], vec![]]);
+    /// assert_eq!(g.adjacency()[0], [(1, Weighted(4))]);
+    /// ```
+    pub fn adjacency(&self) -> &[Vec<(u32, W)>] {
+        &self.graph
+    }
+
+    /// Edges leaving `node`, or `None` if `node` is out of bounds.
+    ///
+    /// ```rust
+    /// use edgewise::{Graph, Unweighted};
+    /// let g = Graph::new(vec![vec![(1, Unweighted(()))], vec![]]);
+    /// assert_eq!(g.get_node(0), Some(&vec![(1, Unweighted(()))]));
+    /// assert_eq!(g.get_node(2), None);
+    /// ```
+    pub fn get_node(&self, node: u32) -> Option<&Vec<(u32, W)>> {
+        self.graph.get(node as usize)
+    }
+
+    /// Mutable access to the edges leaving `node`, or `None` if `node` is out
+    /// of bounds. Edge targets must remain valid node identifiers.
+    pub fn get_node_mut(&mut self, node: u32) -> Option<&mut Vec<(u32, W)>> {
+        self.graph.get_mut(node as usize)
+    }
+
+    /// In-degree of every node, computed in a single pass over the edges.
+    pub fn in_degrees(&self) -> Vec<usize> {
+        let mut in_degree: Vec<usize> = vec![0; self.graph.len()];
+        for (_, v, _) in self.edges() {
+            in_degree[v as usize] += 1;
+        }
+        in_degree
+    }
+
+    /// Whether `node` has no outgoing and no incoming edges.
+    pub fn is_isolated(&self, node: u32) -> Result<bool, GraphError> {
+        self.check_node(node)?;
+        Ok(self.graph[node as usize].is_empty() && !self.edges().any(|(_, v, _)| v == node))
+    }
+
+    fn check_node(&self, node: u32) -> Result<(), GraphError> {
+        if (node as usize) >= self.graph.len() {
+            return Err(GraphError::OutOfBoundsNode { node });
+        }
+        Ok(())
+    }
+
+    fn check_same_nodes<W2>(&self, other: &Graph<W2>) -> Result<(), GraphError> {
+        if self.graph.len() != other.graph.len() {
+            return Err(GraphError::NodeCountMismatch {
+                expected: self.graph.len(),
+                found: other.graph.len(),
             });
         }
+        Ok(())
+    }
+
+    // BFS from `source`; `on_discover(node, parent)` is called once for every
+    // reached node in discovery order, with `parent` being `None` for the
+    // source. The search stops early as soon as `on_discover` returns true.
+    fn bfs_from(
+        &self,
+        source: u32,
+        mut on_discover: impl FnMut(u32, Option<u32>) -> bool,
+    ) -> Result<(), GraphError> {
+        self.check_node(source)?;
         let mut nodes_left_to_process: VecDeque<u32> = VecDeque::new();
         let mut nodes_visited_lookup: Vec<bool> = vec![false; self.graph.len()];
-        let mut nodes_visited: Vec<u32> = Vec::new();
-        nodes_left_to_process.push_back(starting_node);
-        nodes_visited_lookup[starting_node as usize] = true;
-        nodes_visited.push(starting_node);
+        nodes_visited_lookup[source as usize] = true;
+        if on_discover(source, None) {
+            return Ok(());
+        }
+        nodes_left_to_process.push_back(source);
         while let Some(node_to_process) = nodes_left_to_process.pop_front() {
-            if let Some(neighbours_of_node) = self.graph.get(node_to_process as usize) {
-                for &(n, _) in neighbours_of_node {
-                    if !nodes_visited_lookup[n as usize] {
-                        nodes_visited_lookup[n as usize] = true;
-                        nodes_visited.push(n);
-                        nodes_left_to_process.push_back(n);
+            for &(n, _) in &self.graph[node_to_process as usize] {
+                if !nodes_visited_lookup[n as usize] {
+                    nodes_visited_lookup[n as usize] = true;
+                    if on_discover(n, Some(node_to_process)) {
+                        return Ok(());
                     }
+                    nodes_left_to_process.push_back(n);
                 }
             }
         }
-        Ok(nodes_visited)
+        Ok(())
     }
 
-    pub fn dfs(&self, starting_node: u32) -> Result<Vec<u32>, GraphError> {
-        if (starting_node as usize) >= self.graph.len() {
-            return Err(GraphError::OutOfBoundsNode {
-                node: starting_node,
-            });
+    // Neighbour lists of the undirected graph underlying `self`: every edge is
+    // seen from both endpoints, parallel edges are merged and self-loops dropped.
+    fn undirected_neighbours(&self) -> Vec<Vec<u32>> {
+        let mut neighbours: Vec<Vec<u32>> = vec![Vec::new(); self.graph.len()];
+        for (u, v, _) in self.edges() {
+            if u != v {
+                neighbours[u as usize].push(v);
+                neighbours[v as usize].push(u);
+            }
         }
-        let mut nodes_left_to_process: VecDeque<u32> = VecDeque::new();
-        let mut nodes_visited_lookup: Vec<bool> = vec![false; self.graph.len()];
-        let mut nodes_visited: Vec<u32> = Vec::new();
-        nodes_left_to_process.push_back(starting_node);
-        nodes_visited_lookup[starting_node as usize] = true;
-        nodes_visited.push(starting_node);
-        while !nodes_left_to_process.is_empty() {
-            let mut found_unvisited = false;
-            if let Some(&node_to_process) = nodes_left_to_process.back()
-                && let Some(neighbours_of_node) = self.graph.get(node_to_process as usize)
-            {
-                for &(n, _) in neighbours_of_node {
-                    if !nodes_visited_lookup[n as usize] {
-                        nodes_visited_lookup[n as usize] = true;
-                        nodes_visited.push(n);
-                        nodes_left_to_process.push_back(n);
-                        found_unvisited = true;
-                        break;
+        for n in &mut neighbours {
+            n.sort_unstable();
+            n.dedup();
+        }
+        neighbours
+    }
+
+    // Component id of every node in the undirected graph underlying `self`,
+    // numbered by smallest member, together with the number of components.
+    fn undirected_component_ids(&self) -> (Vec<usize>, usize) {
+        let neighbours = self.undirected_neighbours();
+        let mut component: Vec<Option<usize>> = vec![None; self.graph.len()];
+        let mut count = 0;
+        for root in 0..self.graph.len() {
+            if component[root].is_some() {
+                continue;
+            }
+            component[root] = Some(count);
+            let mut stack: Vec<u32> = vec![root as u32];
+            while let Some(node) = stack.pop() {
+                for &n in &neighbours[node as usize] {
+                    if component[n as usize].is_none() {
+                        component[n as usize] = Some(count);
+                        stack.push(n);
                     }
                 }
-                if !found_unvisited {
-                    nodes_left_to_process.pop_back();
-                }
             }
+            count += 1;
         }
-        Ok(nodes_visited)
+        (
+            component.into_iter().map(|c| c.unwrap_or(0)).collect(),
+            count,
+        )
     }
-}
 
-#[allow(private_bounds)]
-impl<W: InsertEdge> Graph<W> {
-    fn insert_edge(&mut self, rng: &mut ThreadRng, i: u32, j: u32, is_directed: bool) {
-        W::insert_edge(self, rng, i, j, is_directed);
+    // GraphML document for the graph; when `weight` is given, each edge carries
+    // its weight as a `weight` data attribute.
+    fn graphml(&self, weight: Option<&dyn Fn(&W) -> u32>) -> String {
+        let mut out = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+        );
+        if weight.is_some() {
+            out.push_str(
+                "  <key id=\"weight\" for=\"edge\" attr.name=\"weight\" attr.type=\"long\"/>\n",
+            );
+        }
+        out.push_str("  <graph id=\"G\" edgedefault=\"directed\">\n");
+        for node in 0..self.graph.len() {
+            out.push_str(&format!("    <node id=\"n{node}\"/>\n"));
+        }
+        for (u, v, w) in self.edges() {
+            match weight {
+                Some(weight) => out.push_str(&format!(
+                    "    <edge source=\"n{u}\" target=\"n{v}\">\n      \
+                     <data key=\"weight\">{}</data>\n    </edge>\n",
+                    weight(w)
+                )),
+                None => out.push_str(&format!("    <edge source=\"n{u}\" target=\"n{v}\"/>\n")),
+            }
+        }
+        out.push_str("  </graph>\n</graphml>\n");
+        out
     }
 
-    pub fn random_graph(num_nodes: u32, probability: f64, is_directed: bool) -> Self {
-        let mut v: Vec<Vec<(u32, W)>> = Vec::new();
-        v.resize_with(num_nodes as usize, Vec::new);
-        let mut graph = Graph::new(v);
-        let mut rng = rand::rng();
-        for i in 0..num_nodes {
-            let z = if is_directed { 0 } else { i + 1 };
-            for j in z..num_nodes {
-                let r: f64 = rng.random();
-                if r < probability {
-                    graph.insert_edge(&mut rng, i, j, is_directed);
+    // An iterator over the edges of the graph.
+    pub fn edges(&self) -> impl Iterator<Item = (u32, u32, &W)> + '_ {
+        self.graph.iter().enumerate().flat_map(|(u, v)| {
+            let x = u as u32; //safe as the number of nodes is capped by u32 anyway
+            v.iter().map(move |(y, w)| (x, *y, w))
+        })
+    }
+
+    /// Iterates over the edges of an undirected graph stored with both
+    /// half-edges, yielding each edge once (as the half-edge with `u <= v`).
+    pub fn undirected_edges(&self) -> impl Iterator<Item = (u32, u32, &W)> + '_ {
+        self.edges().filter(|&(u, v, _)| u <= v)
+    }
+
+    /// All edges sorted by `(from, to)`, independently of insertion order.
+    /// Parallel edges keep their relative order.
+    pub fn sorted_edges(&self) -> Vec<(u32, u32, &W)> {
+        let mut edges: Vec<(u32, u32, &W)> = self.edges().collect();
+        edges.sort_by_key(|&(u, v, _)| (u, v));
+        edges
+    }
+
+    /// Number of edges for each ordered pair of endpoints `(from, to)` that is
+    /// joined by at least one edge; entries above 1 reveal parallel edges.
+    pub fn edge_multiplicities(&self) -> HashMap<(u32, u32), usize> {
+        let mut multiplicities: HashMap<(u32, u32), usize> = HashMap::new();
+        for (u, v, _) in self.edges() {
+            *multiplicities.entry((u, v)).or_insert(0) += 1;
+        }
+        multiplicities
+    }
+
+    /// Checks that consecutive nodes of `walk` are joined by an edge. Empty and
+    /// single-node walks are trivially valid.
+    pub fn is_valid_walk(&self, walk: &[u32]) -> Result<bool, GraphError> {
+        for &node in walk {
+            self.check_node(node)?;
+        }
+        Ok(walk
+            .windows(2)
+            .all(|p| self.graph[p[0] as usize].iter().any(|&(n, _)| n == p[1])))
+    }
+
+    /// Uniform random walk of at most `length` steps from `start`: each step
+    /// follows one of the edges leaving the current node, chosen uniformly
+    /// (parallel edges make a target proportionally more likely). The walk
+    /// stops early at a node without outgoing edges. The returned walk
+    /// includes `start`, so it has at most `length + 1` nodes.
+    pub fn random_walk(
+        &self,
+        start: u32,
+        length: usize,
+        rng: &mut impl Rng,
+    ) -> Result<Vec<u32>, GraphError> {
+        self.check_node(start)?;
+        let mut walk: Vec<u32> = Vec::with_capacity(length + 1);
+        walk.push(start);
+        let mut current = start;
+        for _ in 0..length {
+            let edges = &self.graph[current as usize];
+            if edges.is_empty() {
+                break;
+            }
+            current = edges[rng.random_range(0..edges.len())].0;
+            walk.push(current);
+        }
+        Ok(walk)
+    }
+
+    /// Unweighted graph on the common node set of `self` and `other` with an
+    /// edge `u -> v` whenever both graphs have some edge `u -> v`, whatever
+    /// the weights. Parallel edges are merged. Returns
+    /// [`GraphError::NodeCountMismatch`] if the node counts differ.
+    pub fn intersection(&self, other: &Graph<W>) -> Result<Graph<Unweighted>, GraphError> {
+        self.compare_edges(other, true)
+    }
+
+    /// Unweighted graph on the common node set of `self` and `other` with an
+    /// edge `u -> v` whenever `self` has some edge `u -> v` and `other` has
+    /// none, whatever the weights. Parallel edges are merged. Returns
+    /// [`GraphError::NodeCountMismatch`] if the node counts differ.
+    pub fn edge_difference(&self, other: &Graph<W>) -> Result<Graph<Unweighted>, GraphError> {
+        self.compare_edges(other, false)
+    }
+
+    // Edges `u -> v` of `self` (merged by endpoints) that `other` has too when
+    // `shared` is set, or that `other` lacks otherwise.
+    fn compare_edges(
+        &self,
+        other: &Graph<W>,
+        shared: bool,
+    ) -> Result<Graph<Unweighted>, GraphError> {
+        self.check_same_nodes(other)?;
+        Ok(Graph::new(
+            self.graph
+                .iter()
+                .zip(&other.graph)
+                .map(|(edges, other_edges)| {
+                    let mut targets: Vec<u32> = edges
+                        .iter()
+                        .map(|&(t, _)| t)
+                        .filter(|t| other_edges.iter().any(|(o, _)| o == t) == shared)
+                        .collect();
+                    targets.sort_unstable();
+                    targets.dedup();
+                    targets.into_iter().map(|t| (t, Unweighted(()))).collect()
+                })
+                .collect(),
+        ))
+    }
+
+    /// Topological order of the nodes in which, among all nodes available at
+    /// each step, the smallest id always comes first. The result is therefore
+    /// the lexicographically smallest topological order, which is fully
+    /// deterministic. Returns [`GraphError::CyclePresent`] if the graph is not
+    /// a DAG.
+    pub fn topological_sort_lexicographic(&self) -> Result<Vec<u32>, GraphError> {
+        let mut in_degree = self.in_degrees();
+        let mut available: BinaryHeap<Reverse<u32>> = (0..self.graph.len() as u32)
+            .filter(|&n| in_degree[n as usize] == 0)
+            .map(Reverse)
+            .collect();
+        let mut order: Vec<u32> = Vec::with_capacity(self.graph.len());
+        while let Some(Reverse(node)) = available.pop() {
+            order.push(node);
+            for &(n, _) in &self.graph[node as usize] {
+                in_degree[n as usize] -= 1;
+                if in_degree[n as usize] == 0 {
+                    available.push(Reverse(n));
                 }
             }
         }
-        graph
+        if order.len() != self.graph.len() {
+            return Err(GraphError::CyclePresent);
+        }
+        Ok(order)
     }
-}
 
-impl Graph<Weighted> {
-    pub fn dijkstra(&self, starting_node: u32) -> Result<Vec<Option<u32>>, GraphError> {
-        if (starting_node as usize) >= self.graph.len() {
-            return Err(GraphError::OutOfBoundsNode {
-                node: starting_node,
-            });
+    /// Depth of every node in a DAG: the number of edges on the longest path
+    /// ending at the node, so sources have depth 0. For a schedule this is the
+    /// critical-path length before each task. Returns
+    /// [`GraphError::CyclePresent`] if the graph is not a DAG.
+    pub fn topological_depth(&self) -> Result<Vec<u32>, GraphError> {
+        let mut depth: Vec<u32> = vec![0; self.graph.len()];
+        for u in self.topological_sort_lexicographic()? {
+            for &(v, _) in &self.graph[u as usize] {
+                depth[v as usize] = depth[v as usize].max(depth[u as usize] + 1);
+            }
         }
-        let mut nodes_distance: Vec<Option<u32>> = vec![None; self.graph.len()];
-        let mut nodes_visited: Vec<bool> = vec![false; self.graph.len()];
-        nodes_distance[starting_node as usize] = Some(0);
-        while let Some((current_node, current_distance)) = (0..nodes_visited.len())
-            .filter(|&i| !nodes_visited[i])
-            .filter_map(|i| nodes_distance[i].map(|d| (i, d)))
-            .min_by_key(|&(_, d)| d)
-        {
-            if let Some(neighbors) = self.graph.get(current_node) {
-                for &(neighbor_node, neighbor_weight) in neighbors {
-                    if let Some(new_distance) = current_distance.checked_add(neighbor_weight.0) {
-                        if let Some(neighbor_distance) = nodes_distance[neighbor_node as usize] {
-                            if new_distance < neighbor_distance {
-                                nodes_distance[neighbor_node as usize] = Some(new_distance)
-                            }
-                        } else {
-                            nodes_distance[neighbor_node as usize] = Some(new_distance)
-                        }
+        Ok(depth)
+    }
+
+    /// Bridges of the graph treated as undirected: edges whose removal
+    /// disconnects their endpoints. Each bridge is reported once as `(u, v)`
+    /// with `u < v`, sorted. Parallel edges are merged, so an edge doubled in
+    /// the same direction still counts as a single edge.
+    pub fn bridges(&self) -> Vec<(u32, u32)> {
+        const UNVISITED: u32 = u32::MAX;
+        let neighbours = self.undirected_neighbours();
+        let n = self.graph.len();
+        let mut discovery: Vec<u32> = vec![UNVISITED; n];
+        let mut low: Vec<u32> = vec![0; n];
+        let mut time = 0;
+        let mut bridges: Vec<(u32, u32)> = Vec::new();
+        for root in 0..n as u32 {
+            if discovery[root as usize] != UNVISITED {
+                continue;
+            }
+            // Iterative Tarjan lowlink DFS: frames of (node, parent, next edge).
+            let mut stack: Vec<(u32, u32, usize)> = vec![(root, UNVISITED, 0)];
+            discovery[root as usize] = time;
+            low[root as usize] = time;
+            time += 1;
+            while let Some(&mut (node, parent, ref mut next_edge)) = stack.last_mut() {
+                if let Some(&n) = neighbours[node as usize].get(*next_edge) {
+                    *next_edge += 1;
+                    if n == parent {
+                        continue;
+                    }
+                    if discovery[n as usize] == UNVISITED {
+                        discovery[n as usize] = time;
+                        low[n as usize] = time;
+                        time += 1;
+                        stack.push((n, node, 0));
                     } else {
-                        // New distance for the current_node causes an overflow.
-                        return Err(GraphError::DistanceOverflow {
-                            node_from: current_node as u32,
-                            node_to: neighbor_node,
-                            current_distance,
-                            edge_weight: neighbor_weight.0,
-                        });
+                        low[node as usize] = low[node as usize].min(discovery[n as usize]);
+                    }
+                } else {
+                    stack.pop();
+                    if parent != UNVISITED {
+                        low[parent as usize] = low[parent as usize].min(low[node as usize]);
+                        if low[node as usize] > discovery[parent as usize] {
+                            bridges.push((parent.min(node), parent.max(node)));
+                        }
                     }
                 }
             }
-            nodes_visited[current_node] = true
         }
-        Ok(nodes_distance)
+        bridges.sort();
+        bridges
     }
-}
 
-trait InsertEdge: Sized {
-    fn insert_edge(g: &mut Graph<Self>, rng: &mut ThreadRng, i: u32, j: u32, is_directed: bool);
+    /// Whether every node can be reached from every other one when edge
+    /// directions are ignored (weak connectivity). The empty graph is
+    /// considered connected.
+    pub fn is_connected(&self) -> bool {
+        if self.graph.is_empty() {
+            return true;
+        }
+        let neighbours = self.undirected_neighbours();
+        let mut visited: Vec<bool> = vec![false; self.graph.len()];
+        let mut stack: Vec<u32> = vec![0];
+        visited[0] = true;
+        let mut reached = 1;
+        while let Some(node) = stack.pop() {
+            for &n in &neighbours[node as usize] {
+                if !visited[n as usize] {
+                    visited[n as usize] = true;
+                    reached += 1;
+                    stack.push(n);
+                }
+            }
+        }
+        reached == self.graph.len()
+    }
+
+    /// Whether the graph, treated as undirected, is connected and has no
+    /// bridges, i.e. stays connected after removing any single edge. By
+    /// convention a single-node graph is 2-edge-connected and the empty graph
+    /// is not.
+    pub fn is_2_edge_connected(&self) -> bool {
+        !self.graph.is_empty() && self.is_connected() && self.bridges().is_empty()
+    }
+
+    /// Degree distribution of the graph treated as undirected: entry `d` is the
+    /// number of nodes with exactly `d` distinct neighbours.
+    pub fn degree_histogram(&self) -> Vec<usize> {
+        let neighbours = self.undirected_neighbours();
+        let max_degree = neighbours.iter().map(Vec::len).max().unwrap_or(0);
+        let mut histogram: Vec<usize> = vec![0; max_degree + 1];
+        for ns in &neighbours {
+            histogram[ns.len()] += 1;
+        }
+        if self.graph.is_empty() {
+            histogram.clear();
+        }
+        histogram
+    }
+
+    /// The node with the most distinct neighbours when the graph is treated as
+    /// undirected, together with that degree. Ties go to the smallest id;
+    /// `None` for an empty graph.
+    pub fn max_degree_node(&self) -> Option<(u32, usize)> {
+        self.undirected_neighbours()
+            .iter()
+            .enumerate()
+            .map(|(n, ns)| (n as u32, ns.len()))
+            .min_by_key(|&(n, d)| (Reverse(d), n))
+    }
+
+    /// Nodes of the `k`-core of the graph treated as undirected: what remains
+    /// after repeatedly removing every node with fewer than `k` neighbours.
+    /// The nodes are returned in increasing order.
+    pub fn k_core(&self, k: usize) -> Vec<u32> {
+        self.core_number()
+            .into_iter()
+            .enumerate()
+            .filter(|&(_, c)| c >= k)
+            .map(|(n, _)| n as u32)
+            .collect()
+    }
+
+    /// Core number of every node of the graph treated as undirected: the
+    /// largest `k` such that the node belongs to the `k`-core. Computed by
+    /// peeling off a node of minimum remaining degree at each step.
+    pub fn core_number(&self) -> Vec<usize> {
+        let neighbours = self.undirected_neighbours();
+        let mut degree: Vec<usize> = neighbours.iter().map(Vec::len).collect();
+        let mut removed: Vec<bool> = vec![false; self.graph.len()];
+        let mut core: Vec<usize> = vec![0; self.graph.len()];
+        let mut heap: BinaryHeap<Reverse<(usize, u32)>> = (0..self.graph.len() as u32)
+            .map(|n| Reverse((degree[n as usize], n)))
+            .collect();
+        let mut current = 0;
+        while let Some(Reverse((d, node))) = heap.pop() {
+            // Skip stale entries left behind by degree decreases.
+            if removed[node as usize] || d != degree[node as usize] {
+                continue;
+            }
+            removed[node as usize] = true;
+            current = current.max(d);
+            core[node as usize] = current;
+            for &n in &neighbours[node as usize] {
+                if !removed[n as usize] {
+                    degree[n as usize] -= 1;
+                    heap.push(Reverse((degree[n as usize], n)));
+                }
+            }
+        }
+        core
+    }
+
+    /// Period of a strongly connected graph: the greatest common divisor of
+    /// the lengths of all its directed cycles. Returns `None` if the graph is
+    /// empty, not strongly connected, or has no cycle at all (a single node
+    /// without a self-loop).
+    pub fn period(&self) -> Option<u32> {
+        let n = self.graph.len();
+        if n == 0 {
+            return None;
+        }
+        // BFS levels from node 0; every edge u -> v closes cycles whose lengths
+        // are all multiples of gcd(level[u] + 1 - level[v]).
+        let mut level: Vec<Option<u32>> = vec![None; n];
+        level[0] = Some(0);
+        let mut queue: VecDeque<u32> = VecDeque::from([0]);
+        while let Some(u) = queue.pop_front() {
+            for &(v, _) in &self.graph[u as usize] {
+                if level[v as usize].is_none() {
+                    level[v as usize] = level[u as usize].map(|l| l + 1);
+                    queue.push_back(v);
+                }
+            }
+        }
+        if level.iter().any(Option::is_none) {
+            return None;
+        }
+        // Every node must also reach node 0 back.
+        let mut predecessors: Vec<Vec<u32>> = vec![Vec::new(); n];
+        for (u, v, _) in self.edges() {
+            predecessors[v as usize].push(u);
+        }
+        let mut reaches_root: Vec<bool> = vec![false; n];
+        reaches_root[0] = true;
+        let mut stack: Vec<u32> = vec![0];
+        while let Some(v) = stack.pop() {
+            for &u in &predecessors[v as usize] {
+                if !reaches_root[u as usize] {
+                    reaches_root[u as usize] = true;
+                    stack.push(u);
+                }
+            }
+        }
+        if reaches_root.iter().any(|&r| !r) {
+            return None;
+        }
+        let gcd = |mut a: u32, mut b: u32| {
+            while b != 0 {
+                (a, b) = (b, a % b);
+            }
+            a
+        };
+        let period = self.edges().fold(0, |g, (u, v, _)| {
+            let (lu, lv) = (
+                level[u as usize].unwrap_or(0),
+                level[v as usize].unwrap_or(0),
+            );
+            gcd(g, (lu + 1).abs_diff(lv))
+        });
+        (period != 0).then_some(period)
+    }
+
+    /// Greedy maximal independent set of the graph treated as undirected:
+    /// nodes are considered by increasing degree (ties by id) and kept when
+    /// none of their neighbours has been kept yet. The result is sorted.
+    pub fn maximal_independent_set(&self) -> Vec<u32> {
+        let neighbours = self.undirected_neighbours();
+        let mut order: Vec<u32> = (0..self.graph.len() as u32).collect();
+        order.sort_by_key(|&n| neighbours[n as usize].len());
+        let mut selected: Vec<bool> = vec![false; self.graph.len()];
+        for node in order {
+            if !neighbours[node as usize]
+                .iter()
+                .any(|&n| selected[n as usize])
+            {
+                selected[node as usize] = true;
+            }
+        }
+        (0..self.graph.len() as u32)
+            .filter(|&n| selected[n as usize])
+            .collect()
+    }
+
+    /// Vertex cover of the graph treated as undirected, at most twice as large
+    /// as a minimum one: edges are scanned in order and both endpoints of
+    /// every edge not yet covered are added. The result is sorted.
+    pub fn vertex_cover_approx(&self) -> Vec<u32> {
+        let mut covered: Vec<bool> = vec![false; self.graph.len()];
+        for (u, v, _) in self.edges() {
+            if !covered[u as usize] && !covered[v as usize] {
+                covered[u as usize] = true;
+                covered[v as usize] = true;
+            }
+        }
+        (0..self.graph.len() as u32)
+            .filter(|&n| covered[n as usize])
+            .collect()
+    }
+
+    /// Greedy proper coloring of the graph treated as undirected with colors
+    /// `0..k`: nodes are colored in increasing order, each with the smallest
+    /// color none of its colored neighbours has. On success the color of
+    /// every node is returned.
+    ///
+    /// When every color is taken around a node, it gets the color shared by
+    /// the fewest of its neighbours and the coloring goes on; the error then
+    /// lists all edges `(u, v)`, `u < v`, whose endpoints were forced to the
+    /// same color, sorted. With `k == 0` nothing can be colored: a non-empty
+    /// graph yields an error listing all of its edges.
+    pub fn color_with_k(&self, k: u32) -> Result<Vec<u32>, Vec<(u32, u32)>> {
+        let neighbours = self.undirected_neighbours();
+        if k == 0 && !self.graph.is_empty() {
+            return Err(neighbours
+                .iter()
+                .enumerate()
+                .flat_map(|(u, ns)| {
+                    ns.iter()
+                        .filter(move |&&v| v as usize > u)
+                        .map(move |&v| (u as u32, v))
+                })
+                .collect());
+        }
+        let mut color: Vec<Option<u32>> = vec![None; self.graph.len()];
+        let mut conflicts: Vec<(u32, u32)> = Vec::new();
+        for node in 0..self.graph.len() {
+            let mut uses: Vec<usize> = vec![0; k as usize];
+            for &n in &neighbours[node] {
+                if let Some(c) = color[n as usize] {
+                    uses[c as usize] += 1;
+                }
+            }
+            let c = (0..k).min_by_key(|&c| uses[c as usize]).unwrap_or(0);
+            color[node] = Some(c);
+            for &n in &neighbours[node] {
+                if color[n as usize] == Some(c) {
+                    conflicts.push((n, node as u32));
+                }
+            }
+        }
+        if !conflicts.is_empty() {
+            conflicts.sort_unstable();
+            return Err(conflicts);
+        }
+        Ok(color.into_iter().map(|c| c.unwrap_or(0)).collect())
+    }
+
+    /// [`ConnectivityIndex`] holding the connected components of the graph
+    /// treated as undirected, to which further edges can then be added.
+    pub fn connectivity_index(&self) -> ConnectivityIndex {
+        let mut index = ConnectivityIndex::with_nodes(self.graph.len());
+        for (u, v, _) in self.edges() {
+            index.add_edge(u, v);
+        }
+        index
+    }
+
+    /// Number of edges with one endpoint in `set_a` and the other in `set_b`,
+    /// in either direction. A node appearing in both sets yields
+    /// [`GraphError::InvalidPartition`].
+    pub fn cut_size(&self, set_a: &[u32], set_b: &[u32]) -> Result<usize, GraphError> {
+        let mut side: Vec<Option<bool>> = vec![None; self.graph.len()];
+        for (in_a, set) in [(true, set_a), (false, set_b)] {
+            for &node in set {
+                self.check_node(node)?;
+                if side[node as usize].is_some_and(|s| s != in_a) {
+                    return Err(GraphError::InvalidPartition);
+                }
+                side[node as usize] = Some(in_a);
+            }
+        }
+        Ok(self
+            .edges()
+            .filter(|&(u, v, _)| match (side[u as usize], side[v as usize]) {
+                (Some(a), Some(b)) => a != b,
+                _ => false,
+            })
+            .count())
+    }
+
+    /// Maximum matching between the node sets `left` and `right` computed with
+    /// the Hopcroft–Karp algorithm, in `O(E * sqrt(V))`. Edges are taken in
+    /// either direction; edges with both endpoints on the same side are
+    /// ignored. Returns the matched `(left, right)` pairs sorted by left node.
+    ///
+    /// Every node of the graph must belong to exactly one of `left` and
+    /// `right`, otherwise [`GraphError::InvalidPartition`] is returned.
+    pub fn maximum_bipartite_matching(
+        &self,
+        left: &[u32],
+        right: &[u32],
+    ) -> Result<Vec<(u32, u32)>, GraphError> {
+        const UNMATCHED: u32 = u32::MAX;
+        let n = self.graph.len();
+        // Position of every node within its side, and whether it is on the left.
+        let mut position: Vec<Option<(bool, u32)>> = vec![None; n];
+        for (is_left, side) in [(true, left), (false, right)] {
+            for (i, &node) in side.iter().enumerate() {
+                self.check_node(node)?;
+                if position[node as usize].is_some() {
+                    return Err(GraphError::InvalidPartition);
+                }
+                position[node as usize] = Some((is_left, i as u32));
+            }
+        }
+        if left.len() + right.len() != n {
+            return Err(GraphError::InvalidPartition);
+        }
+        let mut adjacency: Vec<Vec<u32>> = vec![Vec::new(); left.len()];
+        for (u, v, _) in self.edges() {
+            match (position[u as usize], position[v as usize]) {
+                (Some((true, l)), Some((false, r))) | (Some((false, r)), Some((true, l))) => {
+                    adjacency[l as usize].push(r)
+                }
+                _ => {}
+            }
+        }
+        let mut match_left: Vec<u32> = vec![UNMATCHED; left.len()];
+        let mut match_right: Vec<u32> = vec![UNMATCHED; right.len()];
+        let mut layer: Vec<u32> = vec![0; left.len()];
+
+        // Layers free left nodes at 0 and alternating paths outwards; returns
+        // whether some free right node can be reached.
+        let bfs_layers = |match_left: &[u32], match_right: &[u32], layer: &mut [u32]| {
+            let mut queue: VecDeque<u32> = VecDeque::new();
+            for (l, &m) in match_left.iter().enumerate() {
+                if m == UNMATCHED {
+                    layer[l] = 0;
+                    queue.push_back(l as u32);
+                } else {
+                    layer[l] = u32::MAX;
+                }
+            }
+            let mut found_free = false;
+            while let Some(l) = queue.pop_front() {
+                for &r in &adjacency[l as usize] {
+                    let next = match_right[r as usize];
+                    if next == UNMATCHED {
+                        found_free = true;
+                    } else if layer[next as usize] == u32::MAX {
+                        layer[next as usize] = layer[l as usize] + 1;
+                        queue.push_back(next);
+                    }
+                }
+            }
+            found_free
+        };
+
+        fn augment(
+            l: u32,
+            adjacency: &[Vec<u32>],
+            match_left: &mut [u32],
+            match_right: &mut [u32],
+            layer: &mut [u32],
+        ) -> bool {
+            for &r in &adjacency[l as usize] {
+                let next = match_right[r as usize];
+                if next == UNMATCHED
+                    || (layer[next as usize] == layer[l as usize] + 1
+                        && augment(next, adjacency, match_left, match_right, layer))
+                {
+                    match_left[l as usize] = r;
+                    match_right[r as usize] = l;
+                    return true;
+                }
+            }
+            // Dead end: drop the node from the layered graph for this phase.
+            layer[l as usize] = u32::MAX;
+            false
+        }
+
+        while bfs_layers(&match_left, &match_right, &mut layer) {
+            for l in 0..left.len() as u32 {
+                if match_left[l as usize] == UNMATCHED {
+                    augment(l, &adjacency, &mut match_left, &mut match_right, &mut layer);
+                }
+            }
+        }
+        let mut matching: Vec<(u32, u32)> = match_left
+            .iter()
+            .enumerate()
+            .filter(|&(_, &r)| r != UNMATCHED)
+            .map(|(l, &r)| (left[l], right[r as usize]))
+            .collect();
+        matching.sort();
+        Ok(matching)
+    }
+
+    pub fn bfs(&self, starting_node: u32) -> Result<Vec<u32>, GraphError> {
+        let mut nodes_visited: Vec<u32> = Vec::new();
+        self.bfs_visit(starting_node, |n| nodes_visited.push(n))?;
+        Ok(nodes_visited)
+    }
+
+    /// Lazy version of [`Graph::bfs`]: an iterator over the nodes reachable
+    /// from `source`, in BFS order, that explores the graph only as far as it
+    /// is consumed.
+    pub fn bfs_iter(&self, source: u32) -> Result<BfsIter<'_, W>, GraphError> {
+        self.check_node(source)?;
+        let mut visited: Vec<bool> = vec![false; self.graph.len()];
+        visited[source as usize] = true;
+        Ok(BfsIter {
+            graph: self,
+            queue: VecDeque::from([source]),
+            visited,
+        })
+    }
+
+    /// Number of nodes reachable from `starting_node` (itself included),
+    /// computed without collecting them.
+    pub fn reachable_count(&self, starting_node: u32) -> Result<usize, GraphError> {
+        let mut count = 0;
+        self.bfs_visit(starting_node, |_| count += 1)?;
+        Ok(count)
+    }
+
+    /// Calls `visit` on every node reachable from `starting_node`, in BFS
+    /// order, without collecting the visited nodes.
+    pub fn bfs_visit(
+        &self,
+        starting_node: u32,
+        mut visit: impl FnMut(u32),
+    ) -> Result<(), GraphError> {
+        self.bfs_from(starting_node, |node, _| {
+            visit(node);
+            false
+        })
+    }
+
+    /// Returns the first node, in BFS order from `starting_node` (included),
+    /// that satisfies `pred`. The traversal stops as soon as it is found.
+    pub fn bfs_find(
+        &self,
+        starting_node: u32,
+        pred: impl Fn(u32) -> bool,
+    ) -> Result<Option<u32>, GraphError> {
+        let mut found = None;
+        self.bfs_from(starting_node, |node, _| {
+            if pred(node) {
+                found = Some(node);
+            }
+            found.is_some()
+        })?;
+        Ok(found)
+    }
+
+    /// Returns the BFS spanning tree rooted at `starting_node`: each reached
+    /// node keeps only the edge it was discovered through. The tree has the
+    /// same number of nodes as the original graph; unreached nodes are isolated.
+    pub fn bfs_tree(&self, starting_node: u32) -> Result<Graph<Unweighted>, GraphError> {
+        let mut tree: Vec<Vec<(u32, Unweighted)>> = Vec::new();
+        tree.resize_with(self.graph.len(), Vec::new);
+        self.bfs_from(starting_node, |node, parent| {
+            if let Some(parent) = parent {
+                tree[parent as usize].push((node, Unweighted(())));
+            }
+            false
+        })?;
+        Ok(Graph::new(tree))
+    }
+
+    /// Finds a path following edge directions that visits every node exactly
+    /// once, or returns `None` if there is none. The empty graph has the empty
+    /// path.
+    ///
+    /// The search is an exhaustive backtracking DFS with exponential worst-case
+    /// running time, so it is only suitable for small graphs (a few dozen nodes).
+    pub fn hamiltonian_path(&self) -> Option<Vec<u32>> {
+        fn extend<W>(graph: &Graph<W>, path: &mut Vec<u32>, on_path: &mut [bool]) -> bool {
+            if path.len() == graph.graph.len() {
+                return true;
+            }
+            let last = path[path.len() - 1];
+            for &(n, _) in &graph.graph[last as usize] {
+                if !on_path[n as usize] {
+                    on_path[n as usize] = true;
+                    path.push(n);
+                    if extend(graph, path, on_path) {
+                        return true;
+                    }
+                    path.pop();
+                    on_path[n as usize] = false;
+                }
+            }
+            false
+        }
+
+        if self.graph.is_empty() {
+            return Some(Vec::new());
+        }
+        let mut path: Vec<u32> = Vec::with_capacity(self.graph.len());
+        let mut on_path: Vec<bool> = vec![false; self.graph.len()];
+        for start in 0..self.graph.len() as u32 {
+            path.push(start);
+            on_path[start as usize] = true;
+            if extend(self, &mut path, &mut on_path) {
+                return Some(path);
+            }
+            path.pop();
+            on_path[start as usize] = false;
+        }
+        None
+    }
+
+    pub fn dfs(&self, starting_node: u32) -> Result<Vec<u32>, GraphError> {
+        self.check_node(starting_node)?;
+        // Each stack frame keeps the position of the next edge to examine, so
+        // every edge is looked at exactly once: O(V + E) overall.
+        let mut nodes_left_to_process: Vec<(u32, usize)> = Vec::new();
+        let mut nodes_visited_lookup: Vec<bool> = vec![false; self.graph.len()];
+        let mut nodes_visited: Vec<u32> = Vec::new();
+        nodes_left_to_process.push((starting_node, 0));
+        nodes_visited_lookup[starting_node as usize] = true;
+        nodes_visited.push(starting_node);
+        while let Some((node_to_process, next_edge)) = nodes_left_to_process.last_mut() {
+            let neighbours_of_node = &self.graph[*node_to_process as usize];
+            if let Some(&(n, _)) = neighbours_of_node.get(*next_edge) {
+                *next_edge += 1;
+                if !nodes_visited_lookup[n as usize] {
+                    nodes_visited_lookup[n as usize] = true;
+                    nodes_visited.push(n);
+                    nodes_left_to_process.push((n, 0));
+                }
+            } else {
+                nodes_left_to_process.pop();
+            }
+        }
+        Ok(nodes_visited)
+    }
+
+    /// Lazy version of [`Graph::dfs`]: an iterator over the nodes reachable
+    /// from `source`, in DFS preorder, that explores the graph only as far as
+    /// it is consumed.
+    pub fn dfs_iter(&self, source: u32) -> Result<DfsIter<'_, W>, GraphError> {
+        self.check_node(source)?;
+        let mut visited: Vec<bool> = vec![false; self.graph.len()];
+        visited[source as usize] = true;
+        Ok(DfsIter {
+            graph: self,
+            stack: vec![(source, 0)],
+            visited,
+            source: Some(source),
+        })
+    }
+
+    /// Immediate dominator of every node with respect to `root`, computed with
+    /// the iterative algorithm of Cooper, Harvey and Kennedy. The root and the
+    /// nodes unreachable from it have no immediate dominator.
+    pub fn immediate_dominators(&self, root: u32) -> Result<Vec<Option<u32>>, GraphError> {
+        self.check_node(root)?;
+        let n = self.graph.len();
+        // Postorder of the nodes reachable from the root.
+        let mut postorder: Vec<usize> = Vec::new();
+        let mut visited = vec![false; n];
+        let mut stack: Vec<(usize, usize)> = vec![(root as usize, 0)];
+        visited[root as usize] = true;
+        while let Some((u, next_edge)) = stack.last_mut() {
+            let u = *u;
+            if let Some(&(v, _)) = self.graph[u].get(*next_edge) {
+                *next_edge += 1;
+                if !visited[v as usize] {
+                    visited[v as usize] = true;
+                    stack.push((v as usize, 0));
+                }
+            } else {
+                postorder.push(u);
+                stack.pop();
+            }
+        }
+        let mut position: Vec<usize> = vec![0; n];
+        for (i, &u) in postorder.iter().enumerate() {
+            position[u] = i;
+        }
+        let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); n];
+        for (u, v, _) in self.edges() {
+            if visited[u as usize] {
+                predecessors[v as usize].push(u as usize);
+            }
+        }
+        let mut idom: Vec<Option<usize>> = vec![None; n];
+        idom[root as usize] = Some(root as usize);
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &u in postorder.iter().rev().skip(1) {
+                let mut new_idom: Option<usize> = None;
+                for &p in &predecessors[u] {
+                    if idom[p].is_none() {
+                        continue;
+                    }
+                    new_idom = Some(match new_idom {
+                        None => p,
+                        Some(mut a) => {
+                            let mut b = p;
+                            while a != b {
+                                while position[a] < position[b] {
+                                    a = idom[a].unwrap();
+                                }
+                                while position[b] < position[a] {
+                                    b = idom[b].unwrap();
+                                }
+                            }
+                            a
+                        }
+                    });
+                }
+                if new_idom != idom[u] {
+                    idom[u] = new_idom;
+                    changed = true;
+                }
+            }
+        }
+        idom[root as usize] = None;
+        Ok(idom.into_iter().map(|d| d.map(|d| d as u32)).collect())
+    }
+
+    /// Whether the graph contains a directed cycle (self-loops included).
+    pub fn has_cycle(&self) -> bool {
+        self.topological_sort_lexicographic().is_err()
+    }
+
+    /// Condensation of the graph: every strongly connected component is
+    /// collapsed into a single node, and components are joined by an edge
+    /// whenever some edge joins their members (parallel edges merged). The
+    /// result is always acyclic. Components are numbered by their smallest
+    /// member; the returned mapping gives the component of every node.
+    pub fn condensation(&self) -> (Graph<Unweighted>, Vec<u32>) {
+        let n = self.graph.len();
+        // Kosaraju: finishing order of a DFS on the graph, then DFS on the
+        // reversed graph in decreasing finishing time.
+        let mut finished: Vec<u32> = Vec::with_capacity(n);
+        let mut visited: Vec<bool> = vec![false; n];
+        for root in 0..n as u32 {
+            if visited[root as usize] {
+                continue;
+            }
+            visited[root as usize] = true;
+            let mut stack: Vec<(u32, usize)> = vec![(root, 0)];
+            while let Some((node, next_edge)) = stack.last_mut() {
+                if let Some(&(v, _)) = self.graph[*node as usize].get(*next_edge) {
+                    *next_edge += 1;
+                    if !visited[v as usize] {
+                        visited[v as usize] = true;
+                        stack.push((v, 0));
+                    }
+                } else {
+                    finished.push(*node);
+                    stack.pop();
+                }
+            }
+        }
+        let mut predecessors: Vec<Vec<u32>> = vec![Vec::new(); n];
+        for (u, v, _) in self.edges() {
+            predecessors[v as usize].push(u);
+        }
+        let mut component: Vec<Option<u32>> = vec![None; n];
+        let mut count = 0;
+        for &root in finished.iter().rev() {
+            if component[root as usize].is_some() {
+                continue;
+            }
+            component[root as usize] = Some(count);
+            let mut stack: Vec<u32> = vec![root];
+            while let Some(node) = stack.pop() {
+                for &u in &predecessors[node as usize] {
+                    if component[u as usize].is_none() {
+                        component[u as usize] = Some(count);
+                        stack.push(u);
+                    }
+                }
+            }
+            count += 1;
+        }
+        // Renumber the components by smallest member.
+        let mut new_id: Vec<Option<u32>> = vec![None; count as usize];
+        let mut next = 0;
+        let mapping: Vec<u32> = component
+            .into_iter()
+            .map(|c| {
+                let c = c.unwrap_or(0) as usize;
+                *new_id[c].get_or_insert_with(|| {
+                    next += 1;
+                    next - 1
+                })
+            })
+            .collect();
+        let mut targets: Vec<Vec<u32>> = vec![Vec::new(); count as usize];
+        for (u, v, _) in self.edges() {
+            let (cu, cv) = (mapping[u as usize], mapping[v as usize]);
+            if cu != cv {
+                targets[cu as usize].push(cv);
+            }
+        }
+        let condensed = targets
+            .into_iter()
+            .map(|mut ts| {
+                ts.sort_unstable();
+                ts.dedup();
+                ts.into_iter().map(|t| (t, Unweighted(()))).collect()
+            })
+            .collect();
+        (Graph::new(condensed), mapping)
+    }
+
+    /// Classifies every edge by a depth-first search that starts from each
+    /// not yet visited node in increasing order and follows edges in
+    /// adjacency order. Edges are listed in the order they are examined.
+    pub fn classify_edges(&self) -> Vec<((u32, u32), EdgeKind)> {
+        let n = self.graph.len();
+        let mut discovered: Vec<Option<usize>> = vec![None; n];
+        let mut finished = vec![false; n];
+        let mut time = 0;
+        let mut kinds = Vec::new();
+        for root in 0..n {
+            if discovered[root].is_some() {
+                continue;
+            }
+            discovered[root] = Some(time);
+            time += 1;
+            let mut stack: Vec<(usize, usize)> = vec![(root, 0)];
+            while let Some((u, next_edge)) = stack.last_mut() {
+                let u = *u;
+                if let Some(&(v, _)) = self.graph[u].get(*next_edge) {
+                    *next_edge += 1;
+                    let kind = match discovered[v as usize] {
+                        None => {
+                            discovered[v as usize] = Some(time);
+                            time += 1;
+                            stack.push((v as usize, 0));
+                            EdgeKind::Tree
+                        }
+                        Some(_) if !finished[v as usize] => EdgeKind::Back,
+                        Some(d) if discovered[u] < Some(d) => EdgeKind::Forward,
+                        Some(_) => EdgeKind::Cross,
+                    };
+                    kinds.push(((u as u32, v), kind));
+                } else {
+                    finished[u] = true;
+                    stack.pop();
+                }
+            }
+        }
+        kinds
+    }
+}
+
+impl<W: Clone> Graph<W> {
+    /// Inserts the edge `from -> to`; when `is_directed` is false the reverse
+    /// edge `to -> from` is inserted as well, with the same weight (a
+    /// self-loop is only inserted once). Nothing is inserted if either
+    /// endpoint is out of bounds.
+    pub fn insert_edge_pub(
+        &mut self,
+        from: u32,
+        to: u32,
+        weight: W,
+        is_directed: bool,
+    ) -> Result<(), GraphError> {
+        self.check_node(from)?;
+        self.check_node(to)?;
+        if !is_directed && from != to {
+            self.graph[to as usize].push((from, weight.clone()));
+        }
+        self.graph[from as usize].push((to, weight));
+        Ok(())
+    }
+
+    /// Adds the directed edge `from -> to` for every `(from, to, weight)` in
+    /// `edges`. All endpoints are checked before anything is inserted, so on
+    /// [`GraphError::OutOfBoundsNode`] the graph is left unchanged.
+    pub fn add_edges(&mut self, edges: &[(u32, u32, W)]) -> Result<(), GraphError> {
+        for &(from, to, _) in edges {
+            self.check_node(from)?;
+            self.check_node(to)?;
+        }
+        for (from, to, weight) in edges {
+            self.graph[*from as usize].push((*to, weight.clone()));
+        }
+        Ok(())
+    }
+
+    /// Places `other` next to `self` in a single graph: nodes of `self` keep
+    /// their ids and node `i` of `other` becomes `self.node_count() + i`.
+    pub fn disjoint_union(&self, other: &Graph<W>) -> Graph<W> {
+        let offset = self.graph.len() as u32;
+        let mut v: Vec<Vec<(u32, W)>> = self.graph.clone();
+        v.extend(other.graph.iter().map(|edges| {
+            edges
+                .iter()
+                .map(|(t, w)| (t + offset, w.clone()))
+                .collect::<Vec<_>>()
+        }));
+        Graph::new(v)
+    }
+
+    /// Graph on the common node set of `self` and `other` with the edges of
+    /// both. An edge of `other` is skipped when `self` already has an edge
+    /// with the same endpoints, so only the weight from `self` is kept.
+    /// Returns [`GraphError::NodeCountMismatch`] if the node counts differ.
+    pub fn overlay(&self, other: &Graph<W>) -> Result<Graph<W>, GraphError> {
+        self.check_same_nodes(other)?;
+        let mut v: Vec<Vec<(u32, W)>> = self.graph.clone();
+        for (edges, other_edges) in v.iter_mut().zip(&other.graph) {
+            let own: Vec<u32> = edges.iter().map(|&(t, _)| t).collect();
+            edges.extend(
+                other_edges
+                    .iter()
+                    .filter(|(t, _)| !own.contains(t))
+                    .cloned(),
+            );
+        }
+        Ok(Graph::new(v))
+    }
+
+    /// [`Graph::disjoint_union`] of `self` and `other`, plus the directed
+    /// edges `u -> v` for every `(u, v, w)` in `bridges`, where `u` is a node
+    /// of `self` and `v` a node of `other` (before offsetting).
+    ///
+    /// Panics if a bridge endpoint is out of bounds in its graph.
+    pub fn join(&self, other: &Graph<W>, bridges: &[(u32, u32, W)]) -> Graph<W> {
+        let mut joined = self.disjoint_union(other);
+        for (u, v, w) in bridges {
+            assert!(
+                (*u as usize) < self.graph.len(),
+                "Node {u} is out of bounds"
+            );
+            assert!(
+                (*v as usize) < other.graph.len(),
+                "Node {v} is out of bounds"
+            );
+            joined.graph[*u as usize].push((v + self.graph.len() as u32, w.clone()));
+        }
+        joined
+    }
+
+    /// Subgraph induced by the nodes for which `keep` returns true. Kept nodes
+    /// are renumbered consecutively in their original order, and only edges
+    /// between two kept nodes survive.
+    pub fn filter_nodes(&self, keep: impl Fn(u32) -> bool) -> Graph<W> {
+        let mut new_id: Vec<Option<u32>> = vec![None; self.graph.len()];
+        let mut next = 0;
+        for node in 0..self.graph.len() as u32 {
+            if keep(node) {
+                new_id[node as usize] = Some(next);
+                next += 1;
+            }
+        }
+        Graph::new(
+            self.graph
+                .iter()
+                .enumerate()
+                .filter(|&(u, _)| new_id[u].is_some())
+                .map(|(_, edges)| {
+                    edges
+                        .iter()
+                        .filter_map(|(v, w)| new_id[*v as usize].map(|v| (v, w.clone())))
+                        .collect()
+                })
+                .collect(),
+        )
+    }
+
+    /// Returns the graph with every edge reversed.
+    pub fn transpose(&self) -> Graph<W> {
+        let mut v: Vec<Vec<(u32, W)>> = Vec::new();
+        v.resize_with(self.graph.len(), Vec::new);
+        for (u, t, w) in self.edges() {
+            v[t as usize].push((u, w.clone()));
+        }
+        Graph::new(v)
+    }
+
+    /// Returns a copy of the graph with node `i` renamed to `perm[i]`.
+    /// `perm` must be a permutation of `0..node_count()`, otherwise
+    /// [`GraphError::InvalidPermutation`] is returned.
+    pub fn relabel(&self, perm: &[u32]) -> Result<Graph<W>, GraphError> {
+        if perm.len() != self.graph.len() {
+            return Err(GraphError::InvalidPermutation);
+        }
+        let mut seen: Vec<bool> = vec![false; perm.len()];
+        for &p in perm {
+            match seen.get_mut(p as usize) {
+                Some(s) if !*s => *s = true,
+                _ => return Err(GraphError::InvalidPermutation),
+            }
+        }
+        let mut v: Vec<Vec<(u32, W)>> = Vec::new();
+        v.resize_with(self.graph.len(), Vec::new);
+        for (old_node, edges) in self.graph.iter().enumerate() {
+            v[perm[old_node] as usize] = edges
+                .iter()
+                .map(|(target, w)| (perm[*target as usize], w.clone()))
+                .collect();
+        }
+        Ok(Graph::new(v))
+    }
+}
+
+/// Indexing a graph by a node gives the edges leaving that node.
+///
+/// Panics if the node is out of bounds; use [`Graph::get_node`] for a
+/// checked alternative.
+impl<W> Index<u32> for Graph<W> {
+    type Output = [(u32, W)];
+
+    fn index(&self, node: u32) -> &Self::Output {
+        &self.graph[node as usize]
+    }
+}
+
+impl<W> Default for Graph<W> {
+    fn default() -> Self {
+        Graph { graph: Vec::new() }
+    }
+}
+
+/// Lazy breadth-first traversal returned by [`Graph::bfs_iter`]. Nodes are
+/// yielded in the same order as [`Graph::bfs`], but the traversal only
+/// advances as far as the iterator is consumed.
+pub struct BfsIter<'a, W> {
+    graph: &'a Graph<W>,
+    queue: VecDeque<u32>,
+    visited: Vec<bool>,
+}
+
+impl<W> Iterator for BfsIter<'_, W> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        let node = self.queue.pop_front()?;
+        for &(n, _) in &self.graph.graph[node as usize] {
+            if !self.visited[n as usize] {
+                self.visited[n as usize] = true;
+                self.queue.push_back(n);
+            }
+        }
+        Some(node)
+    }
+}
+
+/// Union-find structure over the nodes of a graph, returned by
+/// [`Graph::connectivity_index`], that answers connectivity queries while
+/// edges keep being added, each in near-constant amortized time. Edge
+/// directions are ignored.
+///
+/// Panics if a node passed to one of its methods is out of bounds.
+#[derive(Debug, Clone)]
+pub struct ConnectivityIndex {
+    parent: Vec<u32>,
+    size: Vec<u32>,
+}
+
+impl ConnectivityIndex {
+    // Index over `n` nodes without any edge.
+    fn with_nodes(n: usize) -> Self {
+        ConnectivityIndex {
+            parent: (0..n as u32).collect(),
+            size: vec![1; n],
+        }
+    }
+
+    fn root(&self, mut node: u32) -> u32 {
+        while self.parent[node as usize] != node {
+            node = self.parent[node as usize];
+        }
+        node
+    }
+
+    /// Whether `a` and `b` are connected by the edges added so far.
+    pub fn connected(&self, a: u32, b: u32) -> bool {
+        self.root(a) == self.root(b)
+    }
+
+    /// Records an edge between `a` and `b`. Returns whether it joined two
+    /// components that were not connected before.
+    pub fn add_edge(&mut self, a: u32, b: u32) -> bool {
+        let (mut ra, mut rb) = (self.root(a), self.root(b));
+        // Path compression on both sides.
+        for (mut node, root) in [(a, ra), (b, rb)] {
+            while node != root {
+                node = std::mem::replace(&mut self.parent[node as usize], root);
+            }
+        }
+        if ra == rb {
+            return false;
+        }
+        if self.size[ra as usize] < self.size[rb as usize] {
+            (ra, rb) = (rb, ra);
+        }
+        self.parent[rb as usize] = ra;
+        self.size[ra as usize] += self.size[rb as usize];
+        true
+    }
+}
+
+/// Lazy depth-first traversal returned by [`Graph::dfs_iter`]. Nodes are
+/// yielded in the same preorder as [`Graph::dfs`].
+pub struct DfsIter<'a, W> {
+    graph: &'a Graph<W>,
+    // Frames of (node, position of the next edge to examine).
+    stack: Vec<(u32, usize)>,
+    visited: Vec<bool>,
+    source: Option<u32>,
+}
+
+impl<W> Iterator for DfsIter<'_, W> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        if let Some(source) = self.source.take() {
+            return Some(source);
+        }
+        while let Some((node, next_edge)) = self.stack.last_mut() {
+            if let Some(&(n, _)) = self.graph.graph[*node as usize].get(*next_edge) {
+                *next_edge += 1;
+                if !self.visited[n as usize] {
+                    self.visited[n as usize] = true;
+                    self.stack.push((n, 0));
+                    return Some(n);
+                }
+            } else {
+                self.stack.pop();
+            }
+        }
+        None
+    }
+}
+
+// Range from which `random_graph` draws the weights of weighted edges.
+const DEFAULT_WEIGHT_RANGE: RangeInclusive<u32> = 1..=10;
+
+fn default_weight(rng: &mut ThreadRng) -> u32 {
+    rng.random_range(DEFAULT_WEIGHT_RANGE)
+}
+
+#[allow(private_bounds)]
+impl<W: InsertEdge> Graph<W> {
+    fn insert_edge(
+        &mut self,
+        rng: &mut ThreadRng,
+        sample_weight: &mut impl FnMut(&mut ThreadRng) -> u32,
+        i: u32,
+        j: u32,
+        is_directed: bool,
+    ) {
+        W::insert_edge(self, rng, sample_weight, i, j, is_directed);
+    }
+
+    pub fn random_graph(num_nodes: u32, probability: f64, is_directed: bool) -> Self {
+        Self::random_graph_with(num_nodes, probability, is_directed, default_weight).0
+    }
+
+    /// Same as [`Graph::random_graph`], but returns
+    /// [`GraphError::InvalidProbability`] unless `probability` lies in
+    /// `[0.0, 1.0]`.
+    pub fn random_graph_checked(
+        num_nodes: u32,
+        probability: f64,
+        is_directed: bool,
+    ) -> Result<Self, GraphError> {
+        if !(0.0..=1.0).contains(&probability) {
+            return Err(GraphError::InvalidProbability);
+        }
+        Ok(Self::random_graph(num_nodes, probability, is_directed))
+    }
+
+    // Generates the graph and also reports how many edges were inserted, with
+    // undirected edges counted once.
+    fn random_graph_with(
+        num_nodes: u32,
+        probability: f64,
+        is_directed: bool,
+        mut sample_weight: impl FnMut(&mut ThreadRng) -> u32,
+    ) -> (Self, usize) {
+        let mut v: Vec<Vec<(u32, W)>> = Vec::new();
+        v.resize_with(num_nodes as usize, Vec::new);
+        let mut graph = Graph::new(v);
+        let mut rng = rand::rng();
+        let mut num_edges = 0;
+        for i in 0..num_nodes {
+            let z = if is_directed { 0 } else { i + 1 };
+            for j in z..num_nodes {
+                let r: f64 = rng.random();
+                if r < probability {
+                    graph.insert_edge(&mut rng, &mut sample_weight, i, j, is_directed);
+                    num_edges += 1;
+                }
+            }
+        }
+        (graph, num_edges)
+    }
+}
+
+impl Graph<Unweighted> {
+    /// Weighted copy of the graph in which every edge has weight `weight`.
+    pub fn to_weighted(&self, weight: u32) -> Graph<Weighted> {
+        self.map_weights(|_| Weighted(weight))
+    }
+
+    /// Writes the graph to `writer` in the same edge-list format as its
+    /// [`Display`](fmt::Display) implementation, without building the whole
+    /// output in memory first.
+    pub fn write_edge_list<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        write!(writer, "{self}")
+    }
+
+    /// Exports the graph as a directed GraphML document, e.g. for Gephi or
+    /// yEd. Node `i` gets the id `ni`.
+    pub fn to_graphml(&self) -> String {
+        self.graphml(None)
+    }
+
+    /// Undirected complete graph on `n` nodes: every pair of distinct nodes
+    /// is connected.
+    pub fn complete(n: u32) -> Self {
+        let v: Vec<Vec<(u32, Unweighted)>> = (0..n)
+            .map(|u| {
+                (0..n)
+                    .filter(|&v| v != u)
+                    .map(|v| (v, Unweighted(())))
+                    .collect()
+            })
+            .collect();
+        Graph::new(v)
+    }
+
+    /// Undirected scale-free random graph generated with the Barabási–Albert
+    /// preferential attachment model. The first `m` nodes form a complete
+    /// graph; every further node is connected to `m` distinct existing nodes,
+    /// each chosen with probability proportional to its current degree.
+    /// If `num_nodes <= m` the result is the complete graph on `num_nodes` nodes.
+    pub fn barabasi_albert(num_nodes: u32, m: u32) -> Self {
+        let mut graph = Self::complete(num_nodes.min(m));
+        graph.graph.resize_with(num_nodes as usize, Vec::new);
+        // Every node appears here once per incident edge, so sampling an
+        // entry uniformly picks a node with probability proportional to degree.
+        let mut endpoints: Vec<u32> = graph.edges().map(|(u, _, _)| u).collect();
+        let mut rng = rand::rng();
+        for new_node in m..num_nodes {
+            let mut targets: Vec<u32> = Vec::with_capacity(m as usize);
+            while targets.len() < m as usize {
+                let candidate = if endpoints.is_empty() {
+                    rng.random_range(0..new_node)
+                } else {
+                    endpoints[rng.random_range(0..endpoints.len())]
+                };
+                if !targets.contains(&candidate) {
+                    targets.push(candidate);
+                }
+            }
+            for t in targets {
+                graph.graph[new_node as usize].push((t, Unweighted(())));
+                graph.graph[t as usize].push((new_node, Unweighted(())));
+                endpoints.push(new_node);
+                endpoints.push(t);
+            }
+        }
+        graph
+    }
+
+    /// Undirected small-world random graph generated with the Watts–Strogatz
+    /// model. Nodes are placed on a ring and each one is connected to its `k`
+    /// nearest neighbours (`k / 2` on each side); then every lattice edge
+    /// `(u, v)` is rewired with probability `beta` to `(u, w)`, where `w` is
+    /// chosen uniformly among the nodes not yet adjacent to `u`.
+    ///
+    /// Panics if `k` is odd or not smaller than `num_nodes`.
+    pub fn watts_strogatz(num_nodes: u32, k: u32, beta: f64) -> Self {
+        assert!(k.is_multiple_of(2), "k must be even, got {k}");
+        assert!(
+            k < num_nodes || num_nodes == 0 && k == 0,
+            "k must be smaller than the number of nodes, got k = {k} for {num_nodes} nodes"
+        );
+        let n = num_nodes as usize;
+        let mut neighbours: Vec<Vec<u32>> = vec![Vec::new(); n];
+        for u in 0..num_nodes {
+            for j in 1..=k / 2 {
+                let v = (u + j) % num_nodes;
+                neighbours[u as usize].push(v);
+                neighbours[v as usize].push(u);
+            }
+        }
+        let mut rng = rand::rng();
+        for u in 0..num_nodes {
+            for j in 1..=k / 2 {
+                let v = (u + j) % num_nodes;
+                let r: f64 = rng.random();
+                if r >= beta || !neighbours[u as usize].contains(&v) {
+                    continue;
+                }
+                let candidates: Vec<u32> = (0..num_nodes)
+                    .filter(|&w| w != u && !neighbours[u as usize].contains(&w))
+                    .collect();
+                if candidates.is_empty() {
+                    continue;
+                }
+                let w = candidates[rng.random_range(0..candidates.len())];
+                neighbours[u as usize].retain(|&x| x != v);
+                neighbours[v as usize].retain(|&x| x != u);
+                neighbours[u as usize].push(w);
+                neighbours[w as usize].push(u);
+            }
+        }
+        Graph::new(
+            neighbours
+                .into_iter()
+                .map(|ns| ns.into_iter().map(|v| (v, Unweighted(()))).collect())
+                .collect(),
+        )
+    }
+
+    /// Undirected cycle (ring) on `n` nodes, where node `i` is connected to
+    /// `i + 1` and the last node closes the ring back to node 0. For `n < 3`
+    /// no proper cycle exists: a single node has no edges and two nodes share
+    /// a single edge.
+    pub fn cycle(n: u32) -> Self {
+        let mut v: Vec<Vec<(u32, Unweighted)>> = Vec::new();
+        v.resize_with(n as usize, Vec::new);
+        let num_edges = match n {
+            0 | 1 => 0,
+            2 => 1,
+            _ => n,
+        };
+        for i in 0..num_edges {
+            let j = (i + 1) % n;
+            v[i as usize].push((j, Unweighted(())));
+            v[j as usize].push((i, Unweighted(())));
+        }
+        Graph::new(v)
+    }
+
+    /// Undirected `rows x cols` grid graph where node `r * cols + c` is the
+    /// cell in row `r` and column `c`. Each cell is connected to its orthogonal
+    /// neighbours and, when `diagonal` is set, to its diagonal neighbours too.
+    pub fn grid(rows: u32, cols: u32, diagonal: bool) -> Self {
+        let num_nodes = rows
+            .checked_mul(cols)
+            .expect("The number of nodes of the graph must fit in u32.");
+        let mut v: Vec<Vec<(u32, Unweighted)>> = Vec::new();
+        v.resize_with(num_nodes as usize, Vec::new);
+        let mut connect = |a: u32, b: u32| {
+            v[a as usize].push((b, Unweighted(())));
+            v[b as usize].push((a, Unweighted(())));
+        };
+        for r in 0..rows {
+            for c in 0..cols {
+                let node = r * cols + c;
+                if c + 1 < cols {
+                    connect(node, node + 1);
+                }
+                if r + 1 < rows {
+                    connect(node, node + cols);
+                    if diagonal && c + 1 < cols {
+                        connect(node, node + cols + 1);
+                    }
+                    if diagonal && c > 0 {
+                        connect(node, node + cols - 1);
+                    }
+                }
+            }
+        }
+        Graph::new(v)
+    }
+
+    /// Path from `source` to `target` with the fewest edges, found by BFS, or
+    /// `None` if `target` is unreachable. The path includes both endpoints.
+    pub fn bfs_shortest_path(
+        &self,
+        source: u32,
+        target: u32,
+    ) -> Result<Option<Vec<u32>>, GraphError> {
+        self.check_node(target)?;
+        let mut predecessor: Vec<Option<u32>> = vec![None; self.graph.len()];
+        let mut reached = false;
+        self.bfs_from(source, |node, parent| {
+            predecessor[node as usize] = parent;
+            reached = node == target;
+            reached
+        })?;
+        if !reached {
+            return Ok(None);
+        }
+        let mut path = vec![target];
+        let mut node = target;
+        while let Some(p) = predecessor[node as usize] {
+            path.push(p);
+            node = p;
+        }
+        path.reverse();
+        Ok(Some(path))
+    }
+
+    /// Whether `other` is the same directed graph as `self` up to a renaming
+    /// of the nodes, ignoring the weights of `other`. Parallel edges and
+    /// self-loops must match in number.
+    ///
+    /// The check is a VF2-style backtracking search that extends a partial
+    /// mapping one node at a time, pruning candidates by degree and by the
+    /// edges to already mapped nodes. Its worst case is exponential, so it is
+    /// only suitable for small graphs.
+    pub fn is_isomorphic<W>(&self, other: &Graph<W>) -> bool {
+        // m[u][v]: number of edges u -> v.
+        fn multiplicities<W>(g: &Graph<W>) -> Vec<Vec<u32>> {
+            let n = g.graph.len();
+            let mut m: Vec<Vec<u32>> = vec![vec![0; n]; n];
+            for (u, v, _) in g.edges() {
+                m[u as usize][v as usize] += 1;
+            }
+            m
+        }
+
+        // (out-degree, in-degree) of every node.
+        fn degrees<W>(g: &Graph<W>) -> Vec<(usize, usize)> {
+            g.graph.iter().map(Vec::len).zip(g.in_degrees()).collect()
+        }
+
+        fn extend(
+            a: &[Vec<u32>],
+            b: &[Vec<u32>],
+            da: &[(usize, usize)],
+            db: &[(usize, usize)],
+            mapping: &mut Vec<usize>,
+            used: &mut [bool],
+        ) -> bool {
+            let u = mapping.len();
+            if u == a.len() {
+                return true;
+            }
+            for v in 0..b.len() {
+                if used[v]
+                    || da[u] != db[v]
+                    || a[u][u] != b[v][v]
+                    || mapping
+                        .iter()
+                        .enumerate()
+                        .any(|(x, &y)| a[u][x] != b[v][y] || a[x][u] != b[y][v])
+                {
+                    continue;
+                }
+                used[v] = true;
+                mapping.push(v);
+                if extend(a, b, da, db, mapping, used) {
+                    return true;
+                }
+                mapping.pop();
+                used[v] = false;
+            }
+            false
+        }
+
+        let n = self.graph.len();
+        if n != other.graph.len() || self.edges().count() != other.edges().count() {
+            return false;
+        }
+        let a = multiplicities(self);
+        let b = multiplicities(other);
+        let da = degrees(self);
+        let db = degrees(other);
+        let mut mapping: Vec<usize> = Vec::with_capacity(n);
+        let mut used: Vec<bool> = vec![false; n];
+        extend(&a, &b, &da, &db, &mut mapping, &mut used)
+    }
+
+    /// Largest number of edges on a shortest path between two nodes, following
+    /// edge directions. Exact: it runs a BFS from every node, in `O(V * E)`.
+    /// Returns `None` for the empty graph or if some node cannot reach
+    /// another one.
+    pub fn unweighted_diameter(&self) -> Option<u32> {
+        let n = self.graph.len();
+        let mut diameter = None;
+        for source in 0..n as u32 {
+            let mut depth: Vec<Option<u32>> = vec![None; n];
+            let mut queue: VecDeque<u32> = VecDeque::from([source]);
+            depth[source as usize] = Some(0);
+            let mut farthest = 0;
+            let mut reached = 1;
+            while let Some(u) = queue.pop_front() {
+                let du = depth[u as usize].unwrap_or(0);
+                farthest = du;
+                for &(v, _) in &self.graph[u as usize] {
+                    if depth[v as usize].is_none() {
+                        depth[v as usize] = Some(du + 1);
+                        reached += 1;
+                        queue.push_back(v);
+                    }
+                }
+            }
+            if reached != n {
+                return None;
+            }
+            diameter = diameter.max(Some(farthest));
+        }
+        diameter
+    }
+
+    /// Edge betweenness of every edge of the graph treated as undirected: the
+    /// number of shortest paths between pairs of nodes that go through the
+    /// edge, with paths split evenly when a pair has several shortest paths.
+    /// Computed with Brandes' algorithm in `O(V * E)`. Each edge is reported
+    /// once as `((u, v), score)` with `u < v`, sorted by edge.
+    pub fn edge_betweenness(&self) -> Vec<((u32, u32), f64)> {
+        let neighbours = self.undirected_neighbours();
+        let n = self.graph.len();
+        let mut scores: BTreeMap<(u32, u32), f64> = BTreeMap::new();
+        for (u, ns) in neighbours.iter().enumerate() {
+            for &v in ns.iter().filter(|&&v| v as usize > u) {
+                scores.insert((u as u32, v), 0.0);
+            }
+        }
+        for source in 0..n as u32 {
+            let (order, paths, depth) = Self::shortest_path_counts(&neighbours, source);
+            // Accumulate dependencies from the farthest nodes back to the source.
+            let mut dependency: Vec<f64> = vec![0.0; n];
+            for &w in order.iter().rev() {
+                let dw = depth[w as usize].unwrap_or(0);
+                for &v in &neighbours[w as usize] {
+                    if dw > 0 && depth[v as usize] == Some(dw - 1) {
+                        let share =
+                            paths[v as usize] / paths[w as usize] * (1.0 + dependency[w as usize]);
+                        if let Some(score) = scores.get_mut(&(v.min(w), v.max(w))) {
+                            *score += share;
+                        }
+                        dependency[v as usize] += share;
+                    }
+                }
+            }
+        }
+        // Every unordered pair of nodes was counted from both of its ends.
+        scores
+            .into_iter()
+            .map(|(e, score)| (e, score / 2.0))
+            .collect()
+    }
+
+    /// Laplacian matrix `D - A` of the graph treated as undirected and simple:
+    /// entry `[u][u]` is the number of distinct neighbours of `u`, entry
+    /// `[u][v]` is `-1` if `u` and `v` are adjacent and `0` otherwise.
+    pub fn laplacian(&self) -> Vec<Vec<i64>> {
+        let neighbours = self.undirected_neighbours();
+        let n = neighbours.len();
+        let mut matrix: Vec<Vec<i64>> = vec![vec![0; n]; n];
+        for (u, ns) in neighbours.iter().enumerate() {
+            matrix[u][u] = ns.len() as i64;
+            for &v in ns {
+                matrix[u][v as usize] = -1;
+            }
+        }
+        matrix
+    }
+
+    /// Betweenness centrality of every node of the graph treated as
+    /// undirected: the number of shortest paths between pairs of other nodes
+    /// that go through the node, with paths split evenly when a pair has
+    /// several shortest paths. Computed with Brandes' algorithm in `O(V * E)`.
+    pub fn betweenness_centrality(&self) -> Vec<f64> {
+        let neighbours = self.undirected_neighbours();
+        let mut centrality: Vec<f64> = vec![0.0; self.graph.len()];
+        for source in 0..self.graph.len() as u32 {
+            Self::accumulate_dependencies(&neighbours, source, &mut centrality);
+        }
+        // Every unordered pair of nodes was counted from both of its ends.
+        centrality.iter().map(|c| c / 2.0).collect()
+    }
+
+    /// Unbiased estimate of [`Graph::betweenness_centrality`] that runs
+    /// Brandes' algorithm from `samples` distinct source nodes chosen
+    /// uniformly at random, and scales the result by `node_count / samples`.
+    /// With `samples >= node_count` every node is a source and the result is
+    /// exact; with no samples every estimate is 0.
+    pub fn betweenness_centrality_sampled(&self, samples: usize, rng: &mut impl Rng) -> Vec<f64> {
+        let n = self.graph.len();
+        let samples = samples.min(n);
+        let mut centrality: Vec<f64> = vec![0.0; n];
+        if samples == 0 {
+            return centrality;
+        }
+        let neighbours = self.undirected_neighbours();
+        for source in rand::seq::index::sample(rng, n, samples) {
+            Self::accumulate_dependencies(&neighbours, source as u32, &mut centrality);
+        }
+        let scale = n as f64 / samples as f64 / 2.0;
+        centrality.iter().map(|c| c * scale).collect()
+    }
+
+    // Forward pass of Brandes' algorithm from `source`: the nodes in BFS order,
+    // the number of shortest paths from `source` to each node and each node's
+    // depth, `None` if unreachable.
+    fn shortest_path_counts(
+        neighbours: &[Vec<u32>],
+        source: u32,
+    ) -> (Vec<u32>, Vec<f64>, Vec<Option<u32>>) {
+        let n = neighbours.len();
+        let mut order: Vec<u32> = Vec::with_capacity(n);
+        let mut paths: Vec<f64> = vec![0.0; n];
+        let mut depth: Vec<Option<u32>> = vec![None; n];
+        let mut queue: VecDeque<u32> = VecDeque::new();
+        paths[source as usize] = 1.0;
+        depth[source as usize] = Some(0);
+        queue.push_back(source);
+        while let Some(u) = queue.pop_front() {
+            order.push(u);
+            let du = depth[u as usize].unwrap_or(0);
+            for &v in &neighbours[u as usize] {
+                if depth[v as usize].is_none() {
+                    depth[v as usize] = Some(du + 1);
+                    queue.push_back(v);
+                }
+                if depth[v as usize] == Some(du + 1) {
+                    paths[v as usize] += paths[u as usize];
+                }
+            }
+        }
+        (order, paths, depth)
+    }
+
+    // Adds to `centrality` the dependency of `source` on every other node: the
+    // single-source accumulation step of Brandes' algorithm.
+    fn accumulate_dependencies(neighbours: &[Vec<u32>], source: u32, centrality: &mut [f64]) {
+        let (order, paths, depth) = Self::shortest_path_counts(neighbours, source);
+        let mut dependency: Vec<f64> = vec![0.0; neighbours.len()];
+        for &w in order.iter().rev() {
+            let dw = depth[w as usize].unwrap_or(0);
+            for &v in &neighbours[w as usize] {
+                if dw > 0 && depth[v as usize] == Some(dw - 1) {
+                    dependency[v as usize] +=
+                        paths[v as usize] / paths[w as usize] * (1.0 + dependency[w as usize]);
+                }
+            }
+            if w != source {
+                centrality[w as usize] += dependency[w as usize];
+            }
+        }
+    }
+
+    /// Number of spanning trees of the graph treated as undirected and simple,
+    /// by Kirchhoff's matrix-tree theorem: the determinant of the Laplacian
+    /// with the first row and column removed. It is zero for a disconnected
+    /// or empty graph and saturates at `u64::MAX`, which is also returned when
+    /// an intermediate value of the elimination overflows.
+    pub fn spanning_tree_count(&self) -> u64 {
+        let neighbours = self.undirected_neighbours();
+        let n = neighbours.len();
+        if n == 0 {
+            return 0;
+        }
+        let mut matrix: Vec<Vec<i128>> = vec![vec![0; n - 1]; n - 1];
+        for (u, ns) in neighbours.iter().enumerate().skip(1) {
+            matrix[u - 1][u - 1] = ns.len() as i128;
+            for &v in ns.iter().filter(|&&v| v != 0) {
+                matrix[u - 1][v as usize - 1] = -1;
+            }
+        }
+        // Fraction-free (Bareiss) elimination keeps every entry an exact
+        // integer; the last pivot is the determinant.
+        let size = n - 1;
+        let mut sign = 1;
+        let mut previous_pivot = 1;
+        for k in 0..size {
+            let Some(pivot_row) = (k..size).find(|&r| matrix[r][k] != 0) else {
+                return 0;
+            };
+            if pivot_row != k {
+                matrix.swap(pivot_row, k);
+                sign = -sign;
+            }
+            for i in k + 1..size {
+                for j in k + 1..size {
+                    // The entries are minors of the matrix and may outgrow
+                    // i128 on large dense graphs; the count saturates then.
+                    let Some(entry) = matrix[i][j]
+                        .checked_mul(matrix[k][k])
+                        .zip(matrix[i][k].checked_mul(matrix[k][j]))
+                        .and_then(|(a, b)| a.checked_sub(b))
+                    else {
+                        return u64::MAX;
+                    };
+                    matrix[i][j] = entry / previous_pivot;
+                }
+            }
+            previous_pivot = matrix[k][k];
+        }
+        u64::try_from(sign * previous_pivot).unwrap_or(u64::MAX)
+    }
+
+    /// Number of triangles in the graph, treated as undirected (edge
+    /// directions and parallel edges are ignored).
+    pub fn triangle_count(&self) -> u64 {
+        let neighbours = self.undirected_neighbours();
+        let mut triangles = 0;
+        for (u, nu) in neighbours.iter().enumerate() {
+            for &v in nu.iter().filter(|&&v| v as usize > u) {
+                // Count each triangle u < v < w once, intersecting the sorted
+                // neighbour lists of u and v.
+                let nv = &neighbours[v as usize];
+                let (mut i, mut j) = (0, 0);
+                while i < nu.len() && j < nv.len() {
+                    match nu[i].cmp(&nv[j]) {
+                        Ordering::Less => i += 1,
+                        Ordering::Greater => j += 1,
+                        Ordering::Equal => {
+                            if nu[i] > v {
+                                triangles += 1;
+                            }
+                            i += 1;
+                            j += 1;
+                        }
+                    }
+                }
+            }
+        }
+        triangles
+    }
+
+    /// Local clustering coefficient of `node` in the graph treated as
+    /// undirected: the fraction of pairs of its neighbours that are themselves
+    /// adjacent. Nodes with fewer than two neighbours have coefficient 0.
+    pub fn clustering_coefficient(&self, node: u32) -> Result<f64, GraphError> {
+        self.check_node(node)?;
+        Ok(Self::local_clustering(&self.undirected_neighbours(), node))
+    }
+
+    /// Mean of the local clustering coefficients of all nodes, 0 for the
+    /// empty graph.
+    pub fn average_clustering_coefficient(&self) -> f64 {
+        if self.graph.is_empty() {
+            return 0.0;
+        }
+        let neighbours = self.undirected_neighbours();
+        let total: f64 = (0..self.graph.len() as u32)
+            .map(|node| Self::local_clustering(&neighbours, node))
+            .sum();
+        total / self.graph.len() as f64
+    }
+
+    fn local_clustering(neighbours: &[Vec<u32>], node: u32) -> f64 {
+        let ns = &neighbours[node as usize];
+        let degree = ns.len();
+        if degree < 2 {
+            return 0.0;
+        }
+        let mut links = 0;
+        for (i, &a) in ns.iter().enumerate() {
+            for &b in &ns[i + 1..] {
+                if neighbours[a as usize].binary_search(&b).is_ok() {
+                    links += 1;
+                }
+            }
+        }
+        links as f64 / (degree * (degree - 1) / 2) as f64
+    }
+
+    /// Degree assortativity coefficient of the graph, treated as undirected:
+    /// the Pearson correlation between the degrees at the two ends of each edge.
+    /// Returns `None` when the graph has no edges or all endpoint degrees are
+    /// equal, in which case the coefficient is undefined.
+    pub fn degree_assortativity(&self) -> Option<f64> {
+        let neighbours = self.undirected_neighbours();
+        let mut n = 0.0;
+        let mut sum_xy = 0.0;
+        let mut sum_x = 0.0;
+        let mut sum_x2 = 0.0;
+        // Each undirected edge contributes both (deg u, deg v) and (deg v, deg u),
+        // so both marginals are the same and a single sum suffices for each.
+        for ns in &neighbours {
+            let du = ns.len() as f64;
+            for &v in ns {
+                let dv = neighbours[v as usize].len() as f64;
+                n += 1.0;
+                sum_xy += du * dv;
+                sum_x += du;
+                sum_x2 += du * du;
+            }
+        }
+        if n == 0.0 {
+            return None;
+        }
+        let mean = sum_x / n;
+        let variance = sum_x2 / n - mean * mean;
+        if variance <= f64::EPSILON {
+            return None;
+        }
+        Some((sum_xy / n - mean * mean) / variance)
+    }
+}
+
+impl Graph<Weighted> {
+    /// Largest number of odd-degree nodes accepted by
+    /// [`Graph::route_inspection_length`], whose exact matching needs
+    /// `2^odd` entries of memory.
+    pub const MAX_ROUTE_INSPECTION_ODD_NODES: usize = 20;
+
+    /// Largest node count accepted in the header of [`Graph::from_dimacs`],
+    /// which allocates one adjacency list per declared node up front.
+    pub const MAX_DIMACS_NODES: u32 = 1 << 24;
+
+    /// Unweighted copy of the graph with the same edges.
+    pub fn to_unweighted(&self) -> Graph<Unweighted> {
+        self.map_weights(|_| Unweighted(()))
+    }
+
+    /// Writes the graph to `writer` in the same edge-list format as its
+    /// [`Display`](fmt::Display) implementation, without building the whole
+    /// output in memory first.
+    pub fn write_edge_list<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        write!(writer, "{self}")
+    }
+
+    /// Exports the graph as a directed GraphML document, e.g. for Gephi or
+    /// yEd. Node `i` gets the id `ni` and edge weights are stored in the
+    /// `weight` data attribute.
+    pub fn to_graphml(&self) -> String {
+        self.graphml(Some(&|w: &Weighted| w.0))
+    }
+
+    /// Same as [`Graph::random_graph`], but edge weights are drawn uniformly
+    /// from `weight_range` instead of the default `1..=10`.
+    ///
+    /// Panics if `weight_range` is empty and at least one edge is generated.
+    pub fn random_graph_weighted(
+        num_nodes: u32,
+        probability: f64,
+        is_directed: bool,
+        weight_range: RangeInclusive<u32>,
+    ) -> Self {
+        Self::random_graph_sampled(num_nodes, probability, is_directed, |rng| {
+            rng.random_range(weight_range.clone())
+        })
+    }
+
+    /// Same as [`Graph::random_graph`], but the weight of every edge is drawn
+    /// by calling `sampler` with the generator's random number generator.
+    /// For undirected graphs the sampler is called once per edge, and both
+    /// half-edges get the same weight.
+    pub fn random_graph_sampled(
+        num_nodes: u32,
+        probability: f64,
+        is_directed: bool,
+        sampler: impl FnMut(&mut ThreadRng) -> u32,
+    ) -> Self {
+        Self::random_graph_with(num_nodes, probability, is_directed, sampler).0
+    }
+
+    /// Same as [`Graph::random_graph`], but also returns the number of edges
+    /// that were generated, counting each undirected edge once.
+    pub fn random_graph_with_stats(
+        num_nodes: u32,
+        probability: f64,
+        is_directed: bool,
+    ) -> (Self, usize) {
+        Self::random_graph_with(num_nodes, probability, is_directed, default_weight)
+    }
+
+    /// Serializes the graph in the DIMACS shortest-path format: a
+    /// `p sp N M` problem line followed by one `a u v w` arc line per edge.
+    /// DIMACS node ids are 1-based, so node `i` is written as `i + 1`.
+    pub fn to_dimacs(&self) -> String {
+        let mut out = format!("p sp {} {}\n", self.graph.len(), self.edges().count());
+        for (u, v, w) in self.edges() {
+            out.push_str(&format!("a {} {} {}\n", u + 1, v + 1, w.0));
+        }
+        out
+    }
+
+    /// Reads a graph in the DIMACS shortest-path format produced by
+    /// [`Graph::to_dimacs`]. Comment lines (`c ...`) and blank lines are
+    /// ignored; 1-based DIMACS node ids are shifted back to 0-based ids.
+    /// Headers declaring more than [`Graph::MAX_DIMACS_NODES`] nodes are
+    /// rejected, as is a number of arc lines different from the header's.
+    pub fn from_dimacs(s: &str) -> Result<Graph<Weighted>, ParseError> {
+        let mut adjacency: Option<Vec<Vec<(u32, Weighted)>>> = None;
+        let mut expected = 0;
+        let mut found = 0;
+        for (i, l) in s.lines().enumerate() {
+            let line = i + 1;
+            let fields: Vec<&str> = l.split_whitespace().collect();
+            match fields.as_slice() {
+                [] | ["c", ..] => {}
+                ["p", "sp", n, m] if adjacency.is_none() => {
+                    let n: u32 = n.parse().map_err(|_| ParseError::InvalidLine { line })?;
+                    if n > Self::MAX_DIMACS_NODES {
+                        return Err(ParseError::TooManyNodes { line, nodes: n });
+                    }
+                    expected = m.parse().map_err(|_| ParseError::InvalidLine { line })?;
+                    let mut v: Vec<Vec<(u32, Weighted)>> = Vec::new();
+                    v.resize_with(n as usize, Vec::new);
+                    adjacency = Some(v);
+                }
+                ["a", u, v, w] => {
+                    let adjacency = adjacency.as_mut().ok_or(ParseError::MissingHeader)?;
+                    let node = |x: &str| -> Result<u32, ParseError> {
+                        let node: u32 = x.parse().map_err(|_| ParseError::InvalidLine { line })?;
+                        if node == 0 || node as usize > adjacency.len() {
+                            return Err(ParseError::NodeOutOfRange { line, node });
+                        }
+                        Ok(node - 1)
+                    };
+                    let u = node(u)?;
+                    let v = node(v)?;
+                    let w: u32 = w.parse().map_err(|_| ParseError::InvalidLine { line })?;
+                    adjacency[u as usize].push((v, Weighted(w)));
+                    found += 1;
+                }
+                _ => return Err(ParseError::InvalidLine { line }),
+            }
+        }
+        let adjacency = adjacency.ok_or(ParseError::MissingHeader)?;
+        if expected != found {
+            return Err(ParseError::EdgeCountMismatch { expected, found });
+        }
+        Ok(Graph::new(adjacency))
+    }
+
+    /// Renders the graph as an adjacency matrix with row and column headers.
+    /// Cell `(i, j)` holds the weight of the edge `i -> j`, or `.` if there is
+    /// none; for parallel edges the smallest weight is shown. Intended for
+    /// inspecting small graphs.
+    pub fn to_matrix_string(&self) -> String {
+        let n = self.graph.len();
+        if n == 0 {
+            return String::new();
+        }
+        let mut matrix: Vec<Vec<Option<u32>>> = vec![vec![None; n]; n];
+        for (u, v, w) in self.edges() {
+            let cell = &mut matrix[u as usize][v as usize];
+            *cell = Some(cell.map_or(w.0, |c| c.min(w.0)));
+        }
+        let label_width = (n - 1).to_string().len();
+        let cell_width = matrix
+            .iter()
+            .flatten()
+            .flatten()
+            .map(|w| w.to_string().len())
+            .fold(label_width, usize::max);
+        let mut out = String::new();
+        out.push_str(&" ".repeat(label_width));
+        for j in 0..n {
+            out.push_str(&format!(" {j:>cell_width$}"));
+        }
+        out.push('\n');
+        for (i, row) in matrix.iter().enumerate() {
+            out.push_str(&format!("{i:>label_width$}"));
+            for cell in row {
+                match cell {
+                    Some(w) => out.push_str(&format!(" {w:>cell_width$}")),
+                    None => out.push_str(&format!(" {:>cell_width$}", ".")),
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Random walk of at most `length` steps from `start` in which each step
+    /// follows an edge leaving the current node with probability proportional
+    /// to its weight. The walk stops early at a node whose outgoing edges all
+    /// have weight zero, or that has none. The returned walk includes `start`.
+    pub fn weighted_random_walk(
+        &self,
+        start: u32,
+        length: usize,
+        rng: &mut impl Rng,
+    ) -> Result<Vec<u32>, GraphError> {
+        self.check_node(start)?;
+        let mut walk: Vec<u32> = Vec::with_capacity(length + 1);
+        walk.push(start);
+        let mut current = start;
+        for _ in 0..length {
+            let edges = &self.graph[current as usize];
+            let total: u64 = edges.iter().map(|&(_, w)| w.0 as u64).sum();
+            if total == 0 {
+                break;
+            }
+            let mut r = rng.random_range(0..total);
+            for &(n, w) in edges {
+                if r < w.0 as u64 {
+                    current = n;
+                    break;
+                }
+                r -= w.0 as u64;
+            }
+            walk.push(current);
+        }
+        Ok(walk)
+    }
+
+    /// Connected components of the graph treated as undirected, ordered by
+    /// smallest node and each listed in increasing order, paired with the
+    /// total weight of the edges inside the component. The graph must store
+    /// both half-edges of every edge, which are counted once, as in
+    /// [`Graph::undirected_edges`].
+    pub fn component_weights(&self) -> Vec<(Vec<u32>, u64)> {
+        let (component, count) = self.undirected_component_ids();
+        let mut components: Vec<(Vec<u32>, u64)> = vec![(Vec::new(), 0); count];
+        for (node, &c) in component.iter().enumerate() {
+            components[c].0.push(node as u32);
+        }
+        for (u, _, w) in self.undirected_edges() {
+            components[component[u as usize]].1 += w.0 as u64;
+        }
+        components
+    }
+
+    /// Adjacency matrix of the graph: entry `[i][j]` is the weight of the edge
+    /// `i -> j`, or 0 if there is none. For parallel edges the smallest weight
+    /// is kept, as in [`Graph::to_matrix_string`].
+    pub fn to_adjacency_matrix(&self) -> Vec<Vec<u32>> {
+        let (flat, n) = self.adjacency_flat();
+        if n == 0 {
+            return Vec::new();
+        }
+        flat.chunks(n).map(<[u32]>::to_vec).collect()
+    }
+
+    /// Same matrix as [`Graph::to_adjacency_matrix`], flattened in row-major
+    /// order into a single buffer suitable for numeric libraries, together
+    /// with its dimension `n`: entry `(i, j)` is at `flat[i * n + j]`.
+    pub fn adjacency_flat(&self) -> (Vec<u32>, usize) {
+        let n = self.graph.len();
+        let mut flat: Vec<u32> = vec![0; n * n];
+        let mut present: Vec<bool> = vec![false; n * n];
+        for (u, v, w) in self.edges() {
+            let i = u as usize * n + v as usize;
+            flat[i] = if present[i] { flat[i].min(w.0) } else { w.0 };
+            present[i] = true;
+        }
+        (flat, n)
+    }
+
+    /// Sum of the weights of all edges, counting each directed edge (and so
+    /// both half-edges of an undirected edge).
+    pub fn total_weight(&self) -> u64 {
+        self.edges().map(|(_, _, w)| w.0 as u64).sum()
+    }
+
+    /// Sum of the weights of an undirected graph stored with both half-edges,
+    /// counting each edge once, as in [`Graph::undirected_edges`].
+    pub fn total_undirected_weight(&self) -> u64 {
+        self.undirected_edges().map(|(_, _, w)| w.0 as u64).sum()
+    }
+
+    /// Minimum spanning forest of the graph treated as undirected, computed
+    /// with Kruskal's algorithm: one minimum spanning tree per connected
+    /// component. Edges are reported as `(u, v, weight)` with `u <= v`, in the
+    /// order they were selected (by increasing weight, ties by endpoints).
+    pub fn kruskal(&self) -> Vec<(u32, u32, Weighted)> {
+        let mut edges: Vec<(Weighted, u32, u32)> = self
+            .edges()
+            .filter(|&(u, v, _)| u != v)
+            .map(|(u, v, &w)| (w, u.min(v), u.max(v)))
+            .collect();
+        edges.sort_unstable();
+        let mut index = ConnectivityIndex::with_nodes(self.graph.len());
+        edges
+            .into_iter()
+            .filter(|&(_, u, v)| index.add_edge(u, v))
+            .map(|(w, u, v)| (u, v, w))
+            .collect()
+    }
+
+    /// Total weight of the minimum spanning forest found by
+    /// [`Graph::kruskal`], summed over all components. The forest has fewer
+    /// than `u32::MAX` edges, so the total always fits in a `u64`.
+    pub fn mst_weight(&self) -> Result<u64, GraphError> {
+        Ok(self.kruskal().iter().map(|(_, _, w)| w.0 as u64).sum())
+    }
+
+    /// Sum of the weights of the edges leaving `node`.
+    pub fn weighted_out_degree(&self, node: u32) -> Result<u64, GraphError> {
+        self.check_node(node)?;
+        Ok(self.graph[node as usize]
+            .iter()
+            .map(|&(_, w)| w.0 as u64)
+            .sum())
+    }
+
+    /// Sum of the weights of the edges entering `node`.
+    pub fn weighted_in_degree(&self, node: u32) -> Result<u64, GraphError> {
+        self.check_node(node)?;
+        Ok(self
+            .edges()
+            .filter(|&(_, v, _)| v == node)
+            .map(|(_, _, w)| w.0 as u64)
+            .sum())
+    }
+
+    /// Whether every edge `u -(w)-> v` is matched by an edge `v -(w)-> u` with
+    /// the same weight, i.e. the graph is a valid undirected graph. Parallel
+    /// edges must be matched one for one.
+    pub fn is_symmetric(&self) -> bool {
+        let mut forward: Vec<(u32, u32, u32)> = self.edges().map(|(u, v, w)| (u, v, w.0)).collect();
+        let mut backward: Vec<(u32, u32, u32)> =
+            forward.iter().map(|&(u, v, w)| (v, u, w)).collect();
+        forward.sort_unstable();
+        backward.sort_unstable();
+        forward == backward
+    }
+
+    /// Checks that every edge has a strictly positive weight, as generated by
+    /// [`Graph::random_graph`]. Returns [`GraphError::ZeroWeight`] for the
+    /// first zero-weight edge found.
+    pub fn validate_weights(&self) -> Result<(), GraphError> {
+        match self.edges().find(|&(_, _, w)| w.0 == 0) {
+            Some((node_from, node_to, _)) => Err(GraphError::ZeroWeight { node_from, node_to }),
+            None => Ok(()),
+        }
+    }
+
+    /// Total weight of walking along `path`, using the lightest edge between
+    /// each pair of consecutive nodes. Returns `None` if some consecutive pair
+    /// is not joined by an edge, and [`GraphError::DistanceOverflow`] if the
+    /// total does not fit in a `u32`.
+    pub fn path_weight(&self, path: &[u32]) -> Result<Option<u32>, GraphError> {
+        for &node in path {
+            self.check_node(node)?;
+        }
+        let mut total: u32 = 0;
+        for p in path.windows(2) {
+            let Some(edge_weight) = self.graph[p[0] as usize]
+                .iter()
+                .filter(|&&(n, _)| n == p[1])
+                .map(|&(_, w)| w.0)
+                .min()
+            else {
+                return Ok(None);
+            };
+            total = total
+                .checked_add(edge_weight)
+                .ok_or(GraphError::DistanceOverflow {
+                    node_from: p[0],
+                    node_to: p[1],
+                    current_distance: total.into(),
+                    edge_weight: edge_weight.into(),
+                })?;
+        }
+        Ok(Some(total))
+    }
+
+    /// Shortest distances from `starting_node` to every node, `None` for
+    /// unreachable nodes. When several parallel edges connect the same pair
+    /// of nodes, the lightest one always determines the distance.
+    pub fn dijkstra(&self, starting_node: u32) -> Result<Vec<Option<u32>>, GraphError> {
+        self.dijkstra_from(&[starting_node], None, |_, _, _| {}, |_, _| false)
+    }
+
+    /// Same as [`Graph::dijkstra`], but the distances are wrapped together with
+    /// their source in a [`ShortestPaths`].
+    pub fn dijkstra_result(&self, source: u32) -> Result<ShortestPaths, GraphError> {
+        Ok(ShortestPaths {
+            source,
+            distances: self.dijkstra(source)?,
+        })
+    }
+
+    /// Distance from every node to the nearest of `sources`, `None` for nodes
+    /// that no source can reach.
+    pub fn dijkstra_multi_source(&self, sources: &[u32]) -> Result<Vec<Option<u32>>, GraphError> {
+        self.dijkstra_from(sources, None, |_, _, _| {}, |_, _| false)
+    }
+
+    /// Shortest distances from `source` to each of `targets` that is
+    /// reachable, as `(target, distance)` pairs in the order of `targets`.
+    /// The search stops as soon as every target has been settled.
+    pub fn dijkstra_until(
+        &self,
+        source: u32,
+        targets: &[u32],
+    ) -> Result<Vec<(u32, u32)>, GraphError> {
+        let mut pending = vec![false; self.graph.len()];
+        let mut remaining = 0;
+        for &target in targets {
+            self.check_node(target)?;
+            if !pending[target as usize] {
+                pending[target as usize] = true;
+                remaining += 1;
+            }
+        }
+        let distances = self.dijkstra_from(
+            &[source],
+            None,
+            |_, _, _| {},
+            |node, _| {
+                if pending[node as usize] {
+                    pending[node as usize] = false;
+                    remaining -= 1;
+                }
+                remaining == 0
+            },
+        )?;
+        Ok(targets
+            .iter()
+            .filter_map(|&t| distances[t as usize].map(|d| (t, d)))
+            .collect())
+    }
+
+    /// Length of the shortest path from `source` to `target`, or `None` if
+    /// `target` is unreachable. Runs Dijkstra forwards from `source` and
+    /// backwards from `target` on the transposed graph at the same time, and
+    /// stops once the two searches meet, which usually settles far fewer
+    /// nodes than a full [`Graph::dijkstra`]. Paths whose length overflows a
+    /// `u32` are skipped; [`GraphError::DistanceOverflow`] is only returned if
+    /// one was skipped and no shorter path was found.
+    pub fn bidirectional_dijkstra(
+        &self,
+        source: u32,
+        target: u32,
+    ) -> Result<Option<u32>, GraphError> {
+        self.check_node(source)?;
+        self.check_node(target)?;
+        if source == target {
+            return Ok(Some(0));
+        }
+        let reversed = self.transpose();
+        let graphs = [self, &reversed];
+        let n = self.graph.len();
+        let mut nodes_distance: [Vec<Option<u32>>; 2] = [vec![None; n], vec![None; n]];
+        let mut nodes_visited: [Vec<bool>; 2] = [vec![false; n], vec![false; n]];
+        let mut heaps: [BinaryHeap<Reverse<(u32, u32)>>; 2] =
+            [BinaryHeap::new(), BinaryHeap::new()];
+        for (side, start) in [(0, source), (1, target)] {
+            nodes_distance[side][start as usize] = Some(0);
+            heaps[side].push(Reverse((0, start)));
+        }
+        let mut best: Option<u32> = None;
+        let mut first_overflow: Option<GraphError> = None;
+        while let (Some(&Reverse((top_forward, _))), Some(&Reverse((top_backward, _)))) =
+            (heaps[0].peek(), heaps[1].peek())
+        {
+            if best.is_some_and(|b| top_forward as u64 + top_backward as u64 >= b as u64) {
+                break;
+            }
+            let side = if top_forward <= top_backward { 0 } else { 1 };
+            let Some(Reverse((current_distance, current_node))) = heaps[side].pop() else {
+                break;
+            };
+            if nodes_visited[side][current_node as usize] {
+                continue;
+            }
+            nodes_visited[side][current_node as usize] = true;
+            for &(neighbor_node, neighbor_weight) in &graphs[side].graph[current_node as usize] {
+                // A path through this edge that overflows is longer than any
+                // finite meeting, so it only matters if none is ever found.
+                let overflow = GraphError::DistanceOverflow {
+                    node_from: current_node,
+                    node_to: neighbor_node,
+                    current_distance: current_distance.into(),
+                    edge_weight: neighbor_weight.0.into(),
+                };
+                let Some(new_distance) = current_distance.checked_add(neighbor_weight.0) else {
+                    first_overflow.get_or_insert(overflow);
+                    continue;
+                };
+                if nodes_distance[side][neighbor_node as usize].is_none_or(|d| new_distance < d) {
+                    nodes_distance[side][neighbor_node as usize] = Some(new_distance);
+                    heaps[side].push(Reverse((new_distance, neighbor_node)));
+                }
+                if let Some(other) = nodes_distance[1 - side][neighbor_node as usize] {
+                    match new_distance.checked_add(other) {
+                        Some(total) => best = Some(best.map_or(total, |b| b.min(total))),
+                        None => {
+                            first_overflow.get_or_insert(overflow);
+                        }
+                    }
+                }
+            }
+        }
+        match (best, first_overflow) {
+            (None, Some(overflow)) => Err(overflow),
+            (best, _) => Ok(best),
+        }
+    }
+
+    /// Path from `source` to `target` whose lightest edge is as heavy as
+    /// possible, together with that bottleneck weight, or `None` if `target`
+    /// is unreachable. The trivial path from a node to itself has bottleneck
+    /// `u32::MAX`.
+    pub fn widest_path(
+        &self,
+        source: u32,
+        target: u32,
+    ) -> Result<Option<(u32, Vec<u32>)>, GraphError> {
+        self.check_node(source)?;
+        self.check_node(target)?;
+        let n = self.graph.len();
+        let mut widths: Vec<Option<u32>> = vec![None; n];
+        let mut parents: Vec<Option<u32>> = vec![None; n];
+        let mut nodes_visited: Vec<bool> = vec![false; n];
+        let mut heap: BinaryHeap<(u32, u32)> = BinaryHeap::new();
+        widths[source as usize] = Some(u32::MAX);
+        heap.push((u32::MAX, source));
+        while let Some((current_width, current_node)) = heap.pop() {
+            if nodes_visited[current_node as usize] {
+                continue;
+            }
+            nodes_visited[current_node as usize] = true;
+            if current_node == target {
+                break;
+            }
+            for &(neighbor_node, Weighted(weight)) in &self.graph[current_node as usize] {
+                let new_width = current_width.min(weight);
+                if !nodes_visited[neighbor_node as usize]
+                    && widths[neighbor_node as usize].is_none_or(|w| new_width > w)
+                {
+                    widths[neighbor_node as usize] = Some(new_width);
+                    parents[neighbor_node as usize] = Some(current_node);
+                    heap.push((new_width, neighbor_node));
+                }
+            }
+        }
+        let Some(width) = widths[target as usize] else {
+            return Ok(None);
+        };
+        let mut path = vec![target];
+        while let Some(parent) = parents[*path.last().unwrap() as usize] {
+            path.push(parent);
+        }
+        path.reverse();
+        Ok(Some((width, path)))
+    }
+
+    /// Length of the shortest walk from `source` to `target` that is strictly
+    /// longer than the shortest one, or `None` if there is no such walk.
+    pub fn second_shortest_distance(
+        &self,
+        source: u32,
+        target: u32,
+    ) -> Result<Option<u32>, GraphError> {
+        self.check_node(source)?;
+        self.check_node(target)?;
+        let n = self.graph.len();
+        let mut best: Vec<Option<u32>> = vec![None; n];
+        let mut second: Vec<Option<u32>> = vec![None; n];
+        let mut heap: BinaryHeap<Reverse<(u32, u32)>> = BinaryHeap::new();
+        best[source as usize] = Some(0);
+        heap.push(Reverse((0, source)));
+        while let Some(Reverse((current_distance, current_node))) = heap.pop() {
+            if second[current_node as usize].is_some_and(|d| d < current_distance) {
+                continue;
+            }
+            for &(neighbor_node, neighbor_weight) in &self.graph[current_node as usize] {
+                let Some(new_distance) = current_distance.checked_add(neighbor_weight.0) else {
+                    return Err(GraphError::DistanceOverflow {
+                        node_from: current_node,
+                        node_to: neighbor_node,
+                        current_distance: current_distance.into(),
+                        edge_weight: neighbor_weight.0.into(),
+                    });
+                };
+                let v = neighbor_node as usize;
+                if best[v].is_none_or(|d| new_distance < d) {
+                    second[v] = best[v];
+                    best[v] = Some(new_distance);
+                    heap.push(Reverse((new_distance, neighbor_node)));
+                } else if best[v].is_some_and(|d| new_distance > d)
+                    && second[v].is_none_or(|d| new_distance < d)
+                {
+                    second[v] = Some(new_distance);
+                    heap.push(Reverse((new_distance, neighbor_node)));
+                }
+            }
+        }
+        Ok(second[target as usize])
+    }
+
+    /// Tree of shortest paths from `source`: every reachable node other than
+    /// `source` keeps exactly the edge (with its original weight) through
+    /// which its shortest distance was found. Unreachable nodes are isolated.
+    pub fn shortest_path_tree(&self, source: u32) -> Result<Graph<Weighted>, GraphError> {
+        let mut predecessor: Vec<Option<(u32, Weighted)>> = vec![None; self.graph.len()];
+        self.dijkstra_from(
+            &[source],
+            None,
+            |node, from, weight| predecessor[node as usize] = Some((from, weight)),
+            |_, _| false,
+        )?;
+        let mut tree: Vec<Vec<(u32, Weighted)>> = Vec::new();
+        tree.resize_with(self.graph.len(), Vec::new);
+        for (node, p) in predecessor.into_iter().enumerate() {
+            if let Some((from, weight)) = p {
+                tree[from as usize].push((node as u32, weight));
+            }
+        }
+        Ok(Graph::new(tree))
+    }
+
+    // Dijkstra from every node of `sources` at once; `on_improve(node, from,
+    // weight)` is called whenever the distance of `node` improves through the
+    // edge `from -(weight)-> node`, and `on_settle(node, distance)` once the
+    // distance of `node` is final. The search stops early as soon as
+    // `on_settle` returns true. With a `budget`, edges leading beyond it are
+    // ignored instead of being followed, or reported if they overflow.
+    fn dijkstra_from(
+        &self,
+        sources: &[u32],
+        budget: Option<u32>,
+        mut on_improve: impl FnMut(u32, u32, Weighted),
+        mut on_settle: impl FnMut(u32, u32) -> bool,
+    ) -> Result<Vec<Option<u32>>, GraphError> {
+        let mut nodes_distance: Vec<Option<u32>> = vec![None; self.graph.len()];
+        let mut nodes_visited: Vec<bool> = vec![false; self.graph.len()];
+        let mut heap: BinaryHeap<Reverse<(u32, u32)>> = BinaryHeap::new();
+        for &source in sources {
+            self.check_node(source)?;
+            nodes_distance[source as usize] = Some(0);
+            heap.push(Reverse((0, source)));
+        }
+        while let Some(Reverse((current_distance, current_node))) = heap.pop() {
+            if nodes_visited[current_node as usize] {
+                continue;
+            }
+            nodes_visited[current_node as usize] = true;
+            if on_settle(current_node, current_distance) {
+                break;
+            }
+            for &(neighbor_node, neighbor_weight) in &self.graph[current_node as usize] {
+                if let Some(new_distance) = current_distance.checked_add(neighbor_weight.0) {
+                    if budget.is_some_and(|b| new_distance > b) {
+                        continue;
+                    }
+                    if nodes_distance[neighbor_node as usize].is_none_or(|d| new_distance < d) {
+                        nodes_distance[neighbor_node as usize] = Some(new_distance);
+                        on_improve(neighbor_node, current_node, neighbor_weight);
+                        heap.push(Reverse((new_distance, neighbor_node)));
+                    }
+                } else if budget.is_none() {
+                    // New distance for the current_node causes an overflow.
+                    return Err(GraphError::DistanceOverflow {
+                        node_from: current_node,
+                        node_to: neighbor_node,
+                        current_distance: current_distance.into(),
+                        edge_weight: neighbor_weight.0.into(),
+                    });
+                }
+            }
+        }
+        Ok(nodes_distance)
+    }
+
+    /// Matrix whose entry `[i][j]` tells whether `j` can be reached from `i`
+    /// with a total distance of at most `budget`. Each row comes from a
+    /// Dijkstra search that never follows a path beyond the budget, so heavy
+    /// edges cannot make it overflow.
+    pub fn reachable_within_matrix(&self, budget: u32) -> Result<Vec<Vec<bool>>, GraphError> {
+        (0..self.graph.len() as u32)
+            .map(|source| {
+                let distances =
+                    self.dijkstra_from(&[source], Some(budget), |_, _, _| {}, |_, _| false)?;
+                Ok(distances.into_iter().map(|d| d.is_some()).collect())
+            })
+            .collect()
+    }
+
+    /// Nodes whose shortest-path distance from `source` is exactly `distance`,
+    /// in increasing order.
+    pub fn nodes_at_distance(&self, source: u32, distance: u32) -> Result<Vec<u32>, GraphError> {
+        Ok(self
+            .dijkstra(source)?
+            .into_iter()
+            .enumerate()
+            .filter(|&(_, d)| d == Some(distance))
+            .map(|(n, _)| n as u32)
+            .collect())
+    }
+
+    /// Nodes that cannot be reached from `source`, in increasing order.
+    pub fn unreachable_from(&self, source: u32) -> Result<Vec<u32>, GraphError> {
+        Ok(self
+            .dijkstra(source)?
+            .into_iter()
+            .enumerate()
+            .filter(|(_, d)| d.is_none())
+            .map(|(n, _)| n as u32)
+            .collect())
+    }
+
+    /// Largest shortest-path distance from `node` to any other node, or
+    /// `None` if some node cannot be reached from it.
+    pub fn eccentricity(&self, node: u32) -> Result<Option<u32>, GraphError> {
+        let distances = self.dijkstra(node)?;
+        Ok(distances
+            .into_iter()
+            .try_fold(0, |max, d| d.map(|d| max.max(d))))
+    }
+
+    /// Nodes whose eccentricity equals the radius of the graph, in increasing
+    /// order. Returns [`GraphError::NotConnected`] if no node reaches every
+    /// other node.
+    pub fn center(&self) -> Result<Vec<u32>, GraphError> {
+        let mut eccentricities: Vec<(u32, u32)> = Vec::new();
+        for node in 0..self.graph.len() as u32 {
+            if let Some(e) = self.eccentricity(node)? {
+                eccentricities.push((node, e));
+            }
+        }
+        let Some(radius) = eccentricities.iter().map(|&(_, e)| e).min() else {
+            if self.graph.is_empty() {
+                return Ok(Vec::new());
+            }
+            return Err(GraphError::NotConnected);
+        };
+        Ok(eccentricities
+            .into_iter()
+            .filter(|&(_, e)| e == radius)
+            .map(|(n, _)| n)
+            .collect())
+    }
+
+    /// Mean shortest-path distance over all ordered pairs `(u, v)`, `u != v`,
+    /// such that `v` is reachable from `u`. Returns `None` if no such pair
+    /// exists.
+    pub fn average_shortest_path_length(&self) -> Result<Option<f64>, GraphError> {
+        let mut total: u64 = 0;
+        let mut pairs: u64 = 0;
+        for source in 0..self.graph.len() as u32 {
+            for (target, distance) in self.dijkstra(source)?.into_iter().enumerate() {
+                if let Some(d) = distance
+                    && target != source as usize
+                {
+                    total += d as u64;
+                    pairs += 1;
+                }
+            }
+        }
+        if pairs == 0 {
+            return Ok(None);
+        }
+        Ok(Some(total as f64 / pairs as f64))
+    }
+
+    /// Length of the shortest closed walk that traverses every edge at least
+    /// once (the Chinese postman / route inspection problem). The graph is
+    /// treated as undirected and must store both half-edges of every edge,
+    /// as [`Graph::random_graph`] does for undirected graphs.
+    ///
+    /// Odd-degree nodes are paired up along shortest paths by an exact
+    /// minimum-weight matching, which is exponential in the number of
+    /// odd-degree nodes; a self-loop, stored once, counts 2 towards the degree
+    /// of its node. Returns [`GraphError::NotConnected`] if the nodes with at
+    /// least one edge do not all belong to the same component,
+    /// [`GraphError::TooManyOddNodes`] beyond
+    /// [`Graph::MAX_ROUTE_INSPECTION_ODD_NODES`] odd-degree nodes, and
+    /// [`GraphError::NotUndirected`] if the odd-degree nodes cannot be paired
+    /// up, which only happens when half-edges are missing.
+    pub fn route_inspection_length(&self) -> Result<u64, GraphError> {
+        let total = self.total_undirected_weight();
+        let with_edges: Vec<u32> = (0..self.graph.len() as u32)
+            .filter(|&n| !self.graph[n as usize].is_empty())
+            .collect();
+        let Some(&first) = with_edges.first() else {
+            return Ok(0);
+        };
+        let distances_from_first = self.dijkstra(first)?;
+        if with_edges
+            .iter()
+            .any(|&n| distances_from_first[n as usize].is_none())
+        {
+            return Err(GraphError::NotConnected);
+        }
+        let odd: Vec<u32> = with_edges
+            .into_iter()
+            .filter(|&n| {
+                let edges = &self.graph[n as usize];
+                let self_loops = edges.iter().filter(|&&(v, _)| v == n).count();
+                (edges.len() + self_loops) % 2 == 1
+            })
+            .collect();
+        if odd.len() > Self::MAX_ROUTE_INSPECTION_ODD_NODES {
+            return Err(GraphError::TooManyOddNodes {
+                count: odd.len(),
+                limit: Self::MAX_ROUTE_INSPECTION_ODD_NODES,
+            });
+        }
+        let mut distance: Vec<Vec<u64>> = Vec::with_capacity(odd.len());
+        for &u in &odd {
+            let d = self.dijkstra(u)?;
+            distance.push(
+                odd.iter()
+                    .map(|&v| d[v as usize].map_or(u64::MAX, |x| x as u64))
+                    .collect(),
+            );
+        }
+        // best[mask] is the cheapest way to pair up the odd nodes in `mask`.
+        let mut best: Vec<u64> = vec![u64::MAX; 1 << odd.len()];
+        best[0] = 0;
+        for mask in 0..best.len() {
+            if best[mask] == u64::MAX {
+                continue;
+            }
+            let Some(i) = (0..odd.len()).find(|&i| mask & (1 << i) == 0) else {
+                continue;
+            };
+            for (j, &d) in distance[i].iter().enumerate().skip(i + 1) {
+                if mask & (1 << j) == 0
+                    && let Some(cost) = best[mask].checked_add(d)
+                {
+                    let next = mask | (1 << i) | (1 << j);
+                    best[next] = best[next].min(cost);
+                }
+            }
+        }
+        let pairing = best[best.len() - 1];
+        if pairing == u64::MAX {
+            return Err(GraphError::NotUndirected);
+        }
+        total.checked_add(pairing).ok_or(GraphError::WeightOverflow)
+    }
+
+    /// Minimum mean weight over all directed cycles, computed with Karp's
+    /// algorithm in `O(V * E)` time and `O(V^2)` memory. Returns `None` if the
+    /// graph is acyclic.
+    pub fn minimum_mean_cycle(&self) -> Option<f64> {
+        let n = self.graph.len();
+        // walk[k][v]: minimum weight of a walk with exactly k edges ending at v,
+        // starting anywhere (as if from a virtual source joined to every node).
+        let mut walk: Vec<Vec<Option<u64>>> = vec![vec![Some(0); n]];
+        for k in 1..=n {
+            let mut row: Vec<Option<u64>> = vec![None; n];
+            for (u, v, w) in self.edges() {
+                if let Some(d) = walk[k - 1][u as usize] {
+                    let candidate = d + w.0 as u64;
+                    if row[v as usize].is_none_or(|c| candidate < c) {
+                        row[v as usize] = Some(candidate);
+                    }
+                }
+            }
+            walk.push(row);
+        }
+        (0..n)
+            .filter_map(|v| {
+                let dn = walk[n][v]? as f64;
+                (0..n)
+                    .filter_map(|k| walk[k][v].map(|dk| (dn - dk as f64) / (n - k) as f64))
+                    .reduce(f64::max)
+            })
+            .reduce(f64::min)
+    }
+}
+
+impl Graph<SignedWeighted> {
+    /// Whether some edge has a strictly negative weight.
+    pub fn has_negative_edges(&self) -> bool {
+        self.edges().any(|(_, _, w)| w.0 < 0)
+    }
+
+    /// Shortest distances from `starting_node` computed with Dijkstra's
+    /// algorithm, which is only correct without negative edges. Returns
+    /// [`GraphError::NegativeEdge`] for the first negative edge found, in
+    /// which case [`Graph::bellman_ford`] should be used instead, and
+    /// [`GraphError::DistanceOverflow`] if a distance does not fit in an `i64`.
+    pub fn dijkstra(&self, starting_node: u32) -> Result<Vec<Option<i64>>, GraphError> {
+        self.check_node(starting_node)?;
+        if let Some((node_from, node_to, _)) = self.edges().find(|&(_, _, w)| w.0 < 0) {
+            return Err(GraphError::NegativeEdge { node_from, node_to });
+        }
+        let mut nodes_distance: Vec<Option<i64>> = vec![None; self.graph.len()];
+        let mut nodes_visited: Vec<bool> = vec![false; self.graph.len()];
+        let mut heap: BinaryHeap<Reverse<(i64, u32)>> = BinaryHeap::new();
+        nodes_distance[starting_node as usize] = Some(0);
+        heap.push(Reverse((0, starting_node)));
+        while let Some(Reverse((current_distance, current_node))) = heap.pop() {
+            if nodes_visited[current_node as usize] {
+                continue;
+            }
+            nodes_visited[current_node as usize] = true;
+            for &(neighbor_node, neighbor_weight) in &self.graph[current_node as usize] {
+                let new_distance = Self::relax(
+                    current_node,
+                    neighbor_node,
+                    current_distance,
+                    neighbor_weight.0,
+                )?;
+                if nodes_distance[neighbor_node as usize].is_none_or(|d| new_distance < d) {
+                    nodes_distance[neighbor_node as usize] = Some(new_distance);
+                    heap.push(Reverse((new_distance, neighbor_node)));
+                }
+            }
+        }
+        Ok(nodes_distance)
+    }
+
+    /// Shortest distances from `starting_node` in a graph that may contain
+    /// negative edges, computed with the Bellman–Ford algorithm in `O(V * E)`.
+    /// Returns [`GraphError::NegativeCycle`] if a negative cycle is reachable
+    /// from `starting_node`, and [`GraphError::DistanceOverflow`] if a
+    /// distance does not fit in an `i64`.
+    pub fn bellman_ford(&self, starting_node: u32) -> Result<Vec<Option<i64>>, GraphError> {
+        self.check_node(starting_node)?;
+        let mut nodes_distance: Vec<Option<i64>> = vec![None; self.graph.len()];
+        nodes_distance[starting_node as usize] = Some(0);
+        for _ in 1..self.graph.len() {
+            let mut changed = false;
+            for (u, v, w) in self.edges() {
+                if let Some(du) = nodes_distance[u as usize] {
+                    let new_distance = Self::relax(u, v, du, w.0)?;
+                    if nodes_distance[v as usize].is_none_or(|dv| new_distance < dv) {
+                        nodes_distance[v as usize] = Some(new_distance);
+                        changed = true;
+                    }
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+        for (u, v, w) in self.edges() {
+            if let (Some(du), Some(dv)) = (nodes_distance[u as usize], nodes_distance[v as usize])
+                && Self::relax(u, v, du, w.0)? < dv
+            {
+                return Err(GraphError::NegativeCycle { node: v });
+            }
+        }
+        Ok(nodes_distance)
+    }
+
+    /// Same result as [`Graph::bellman_ford`], computed with the queue-based
+    /// Shortest Path Faster Algorithm: only nodes whose distance just changed
+    /// are relaxed again, which is much faster on typical sparse graphs. A
+    /// shortest path found with `V` or more edges reveals a negative cycle.
+    pub fn spfa(&self, starting_node: u32) -> Result<Vec<Option<i64>>, GraphError> {
+        self.check_node(starting_node)?;
+        let n = self.graph.len();
+        let mut nodes_distance: Vec<Option<i64>> = vec![None; n];
+        let mut path_edges: Vec<usize> = vec![0; n];
+        let mut in_queue: Vec<bool> = vec![false; n];
+        let mut queue: VecDeque<u32> = VecDeque::new();
+        nodes_distance[starting_node as usize] = Some(0);
+        queue.push_back(starting_node);
+        in_queue[starting_node as usize] = true;
+        while let Some(u) = queue.pop_front() {
+            in_queue[u as usize] = false;
+            let Some(du) = nodes_distance[u as usize] else {
+                continue;
+            };
+            for &(v, w) in &self.graph[u as usize] {
+                let new_distance = Self::relax(u, v, du, w.0)?;
+                if nodes_distance[v as usize].is_none_or(|dv| new_distance < dv) {
+                    nodes_distance[v as usize] = Some(new_distance);
+                    path_edges[v as usize] = path_edges[u as usize] + 1;
+                    if path_edges[v as usize] >= n {
+                        return Err(GraphError::NegativeCycle { node: v });
+                    }
+                    if !in_queue[v as usize] {
+                        in_queue[v as usize] = true;
+                        queue.push_back(v);
+                    }
+                }
+            }
+        }
+        Ok(nodes_distance)
+    }
+
+    // Distance to `v` through the edge `u -(w)-> v` when `u` is at distance
+    // `du`, or `DistanceOverflow` if it does not fit in an `i64`.
+    fn relax(u: u32, v: u32, du: i64, w: i64) -> Result<i64, GraphError> {
+        du.checked_add(w).ok_or(GraphError::DistanceOverflow {
+            node_from: u,
+            node_to: v,
+            current_distance: du,
+            edge_weight: w,
+        })
+    }
+}
+
+trait InsertEdge: Sized {
+    fn insert_edge(
+        g: &mut Graph<Self>,
+        rng: &mut ThreadRng,
+        sample_weight: &mut impl FnMut(&mut ThreadRng) -> u32,
+        i: u32,
+        j: u32,
+        is_directed: bool,
+    );
 }
 
 impl InsertEdge for Unweighted {
     fn insert_edge(
         g: &mut Graph<Unweighted>,
         _rng: &mut ThreadRng,
+        _sample_weight: &mut impl FnMut(&mut ThreadRng) -> u32,
         i: u32,
         j: u32,
         is_directed: bool,
@@ -232,11 +3422,12 @@ impl InsertEdge for Weighted {
     fn insert_edge(
         g: &mut Graph<Weighted>,
         rng: &mut ThreadRng,
+        sample_weight: &mut impl FnMut(&mut ThreadRng) -> u32,
         i: u32,
         j: u32,
         is_directed: bool,
     ) {
-        let w: u32 = rng.random_range(1..=10);
+        let w: u32 = sample_weight(rng);
         let u = g
             .graph
             .get_mut(i as usize)
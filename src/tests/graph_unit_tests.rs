@@ -1,5 +1,6 @@
 use crate::*;
 use once_cell::sync::Lazy;
+use rand::{SeedableRng, rngs::StdRng};
 
 static TEST_GRAPH_UNWEIGHTED: Lazy<Graph<Unweighted>> = Lazy::new(|| {
     Graph::new(vec![
@@ -37,6 +38,17 @@ static TEST_GRAPH_WEIGHTED: Lazy<Graph<Weighted>> = Lazy::new(|| {
     ])
 });
 
+// Undirected star with centre 0 and leaves 1..=leaves.
+fn star_graph(leaves: u32) -> Graph<Unweighted> {
+    let mut v = vec![
+        (1..=leaves)
+            .map(|l| (l, Unweighted(())))
+            .collect::<Vec<_>>(),
+    ];
+    v.extend((0..leaves).map(|_| vec![(0, Unweighted(()))]));
+    Graph::new(v)
+}
+
 #[test]
 fn random_gen_weights_gr_zero() {
     let g: Graph<Weighted> = Graph::random_graph(10, 0.5, true);
@@ -173,3 +185,2443 @@ fn basic_dijkstra_test() {
         Err(GraphError::OutOfBoundsNode { node: 15 })
     ));
 }
+
+#[test]
+fn bfs_tree_test() {
+    for source in [0, 3] {
+        let tree = TEST_GRAPH_UNWEIGHTED
+            .bfs_tree(source)
+            .expect("bfs_tree(source) resulted in an error unexpectedly");
+        let mut reachable = TEST_GRAPH_UNWEIGHTED
+            .bfs(source)
+            .expect("bfs(source) resulted in an error unexpectedly");
+        reachable.sort();
+        let tree_edges: Vec<_> = tree.edges().map(|(u, v, _)| (u, v)).collect();
+        // A tree on the reached nodes has exactly one edge fewer than nodes,
+        // and every reached node except the root is entered by exactly one
+        // tree edge, so it has no cycle.
+        assert_eq!(tree_edges.len(), reachable.len() - 1);
+        for &node in &reachable {
+            let incoming = tree_edges.iter().filter(|&&(_, v)| v == node).count();
+            assert_eq!(incoming, if node == source { 0 } else { 1 });
+        }
+        // The tree still reaches every node the original graph reaches.
+        let mut tree_reachable = tree
+            .bfs(source)
+            .expect("bfs(source) resulted in an error unexpectedly");
+        tree_reachable.sort();
+        assert_eq!(tree_reachable, reachable);
+    }
+    assert!(matches!(
+        TEST_GRAPH_UNWEIGHTED.bfs_tree(6),
+        Err(GraphError::OutOfBoundsNode { node: 6 })
+    ));
+}
+
+#[test]
+fn degree_assortativity_star_test() {
+    let star = star_graph(4);
+    let r = star
+        .degree_assortativity()
+        .expect("assortativity of a star is defined");
+    assert!(r < 0.0, "a star must be disassortative, got {r}");
+    // A triangle is regular, so the coefficient is undefined.
+    let triangle: Graph<Unweighted> = Graph::new(vec![
+        vec![(1, Unweighted(())), (2, Unweighted(()))],
+        vec![(0, Unweighted(())), (2, Unweighted(()))],
+        vec![(0, Unweighted(())), (1, Unweighted(()))],
+    ]);
+    assert_eq!(triangle.degree_assortativity(), None);
+}
+
+#[test]
+fn random_gen_weighted_custom_range() {
+    let g = Graph::random_graph_weighted(10, 0.5, true, 5..=5);
+    for (_, _, w) in g.edges() {
+        assert_eq!(*w, Weighted(5));
+    }
+    let g = Graph::random_graph_weighted(10, 0.5, false, 20..=30);
+    for (_, _, w) in g.edges() {
+        assert!((20..=30).contains(&w.0));
+    }
+}
+
+#[test]
+fn default_graph_is_empty() {
+    assert_eq!(Graph::<Unweighted>::default().node_count(), 0);
+    let mut g: Graph<Weighted> = Graph::default();
+    let a = g.add_node();
+    let b = g.add_node();
+    g.add_edge(a, b, Weighted(3))
+        .expect("add_edge(0, 1) resulted in an error unexpectedly");
+    assert_eq!(g.node_count(), 2);
+    assert_eq!(g, Graph::new(vec![vec![(1, Weighted(3))], vec![]]));
+    assert!(matches!(
+        g.add_edge(a, 2, Weighted(1)),
+        Err(GraphError::OutOfBoundsNode { node: 2 })
+    ));
+}
+
+#[test]
+fn grid_graph_test() {
+    let g = Graph::grid(2, 2, false);
+    assert_eq!(g.node_count(), 4);
+    let mut edges: Vec<_> = g
+        .edges()
+        .filter(|&(u, v, _)| u < v)
+        .map(|(u, v, _)| (u, v))
+        .collect();
+    edges.sort();
+    assert_eq!(edges, vec![(0, 1), (0, 2), (1, 3), (2, 3)]);
+    assert_eq!(g.edges().count(), 8);
+    let g = Graph::grid(2, 2, true);
+    assert_eq!(g.edges().count(), 12);
+    let g = Graph::grid(3, 4, false);
+    // 3 rows of 3 horizontal edges and 4 columns of 2 vertical edges.
+    assert_eq!(g.edges().count(), 2 * (3 * 3 + 4 * 2));
+}
+
+#[test]
+fn complete_graph_test() {
+    let g = Graph::complete(4);
+    assert_eq!(g.node_count(), 4);
+    // 4 * 3 / 2 undirected edges, each stored in both directions.
+    assert_eq!(g.edges().count(), 12);
+    assert!(g.edges().all(|(u, v, _)| u != v));
+}
+
+#[test]
+fn cycle_graph_test() {
+    let g = Graph::cycle(5);
+    assert_eq!(g.node_count(), 5);
+    let mut reachable = g.bfs(0).expect("bfs(0) resulted in an error unexpectedly");
+    reachable.sort();
+    assert_eq!(reachable, vec![0, 1, 2, 3, 4]);
+    for n in 0..5 {
+        assert_eq!(g.edges().filter(|&(u, _, _)| u == n).count(), 2);
+    }
+    assert_eq!(Graph::cycle(2).edges().count(), 2);
+    assert_eq!(Graph::cycle(1).edges().count(), 0);
+}
+
+#[test]
+fn relabel_test() {
+    let g = Graph::new(vec![
+        vec![(1, Weighted(2)), (2, Weighted(5))],
+        vec![(2, Weighted(1))],
+        vec![],
+    ]);
+    let perm = [2, 1, 0];
+    let relabelled = g
+        .relabel(&perm)
+        .expect("relabel resulted in an error unexpectedly");
+    let expected = Graph::new(vec![
+        vec![],
+        vec![(0, Weighted(1))],
+        vec![(1, Weighted(2)), (0, Weighted(5))],
+    ]);
+    assert_eq!(relabelled, expected);
+    for (u, v, w) in g.edges() {
+        assert!(
+            relabelled
+                .edges()
+                .any(|(u1, v1, w1)| u1 == perm[u as usize] && v1 == perm[v as usize] && w1 == w)
+        );
+    }
+    assert_eq!(relabelled.relabel(&perm), Ok(g));
+    assert!(matches!(
+        TEST_GRAPH_WEIGHTED.relabel(&[0, 1, 2]),
+        Err(GraphError::InvalidPermutation)
+    ));
+    assert!(matches!(
+        expected.relabel(&[0, 0, 1]),
+        Err(GraphError::InvalidPermutation)
+    ));
+    assert!(matches!(
+        expected.relabel(&[0, 1, 3]),
+        Err(GraphError::InvalidPermutation)
+    ));
+}
+
+#[test]
+fn hamiltonian_path_test() {
+    // Undirected path 2 - 0 - 3 - 1.
+    let path: Graph<Unweighted> = Graph::new(vec![
+        vec![(2, Unweighted(())), (3, Unweighted(()))],
+        vec![(3, Unweighted(()))],
+        vec![(0, Unweighted(()))],
+        vec![(0, Unweighted(())), (1, Unweighted(()))],
+    ]);
+    let found = path
+        .hamiltonian_path()
+        .expect("a path graph has a Hamiltonian path");
+    assert_eq!(found.len(), 4);
+    assert!(
+        found
+            .windows(2)
+            .all(|p| path.edges().any(|(u, v, _)| u == p[0] && v == p[1]))
+    );
+    let mut nodes = found.clone();
+    nodes.sort();
+    assert_eq!(nodes, vec![0, 1, 2, 3]);
+    let star = star_graph(4);
+    assert_eq!(star.hamiltonian_path(), None);
+}
+
+#[test]
+fn write_edge_list_matches_display() {
+    let mut buffer: Vec<u8> = Vec::new();
+    TEST_GRAPH_WEIGHTED
+        .write_edge_list(&mut buffer)
+        .expect("writing to a Vec<u8> cannot fail");
+    assert_eq!(
+        String::from_utf8(buffer).expect("edge list is valid UTF-8"),
+        TEST_GRAPH_WEIGHTED.to_string()
+    );
+    let mut buffer: Vec<u8> = Vec::new();
+    TEST_GRAPH_UNWEIGHTED
+        .write_edge_list(&mut buffer)
+        .expect("writing to a Vec<u8> cannot fail");
+    assert_eq!(
+        String::from_utf8(buffer).expect("edge list is valid UTF-8"),
+        TEST_GRAPH_UNWEIGHTED.to_string()
+    );
+}
+
+#[test]
+fn to_matrix_string_test() {
+    let g = Graph::new(vec![vec![(1, Weighted(4))], vec![(0, Weighted(7))]]);
+    assert_eq!(g.to_matrix_string(), "  0 1\n0 . 4\n1 7 .\n");
+    let g = Graph::new(vec![
+        vec![(0, Weighted(12)), (1, Weighted(30)), (1, Weighted(3))],
+        vec![],
+    ]);
+    assert_eq!(g.to_matrix_string(), "   0  1\n0 12  3\n1  .  .\n");
+    assert_eq!(Graph::<Weighted>::default().to_matrix_string(), "");
+}
+
+#[test]
+fn dijkstra_parallel_edges_use_lightest() {
+    let heavy_first = Graph::new(vec![
+        vec![(1, Weighted(5)), (1, Weighted(2))],
+        vec![(2, Weighted(1)), (2, Weighted(1))],
+        vec![],
+    ]);
+    let light_first = Graph::new(vec![
+        vec![(1, Weighted(2)), (1, Weighted(5))],
+        vec![(2, Weighted(1)), (2, Weighted(1))],
+        vec![],
+    ]);
+    let expected = vec![Some(0), Some(2), Some(3)];
+    assert_eq!(heavy_first.dijkstra(0), Ok(expected.clone()));
+    assert_eq!(light_first.dijkstra(0), Ok(expected));
+}
+
+#[test]
+fn weighted_degree_test() {
+    assert_eq!(TEST_GRAPH_WEIGHTED.weighted_out_degree(0), Ok(4 + 1));
+    assert_eq!(TEST_GRAPH_WEIGHTED.weighted_out_degree(2), Ok(2 + 5 + 8));
+    // Node 6 is entered from 3, 4 and 7.
+    assert_eq!(TEST_GRAPH_WEIGHTED.weighted_in_degree(6), Ok(3 + 2 + 3));
+    assert_eq!(TEST_GRAPH_WEIGHTED.weighted_in_degree(0), Ok(0));
+    let heavy = Graph::new(vec![
+        vec![(1, Weighted(u32::MAX)), (1, Weighted(u32::MAX))],
+        vec![],
+    ]);
+    assert_eq!(heavy.weighted_out_degree(0), Ok(2 * u32::MAX as u64));
+    assert_eq!(heavy.weighted_in_degree(1), Ok(2 * u32::MAX as u64));
+    assert!(matches!(
+        TEST_GRAPH_WEIGHTED.weighted_in_degree(15),
+        Err(GraphError::OutOfBoundsNode { node: 15 })
+    ));
+}
+
+#[test]
+fn bfs_visit_matches_bfs() {
+    for source in [0, 3] {
+        let mut visited: Vec<u32> = Vec::new();
+        TEST_GRAPH_UNWEIGHTED
+            .bfs_visit(source, |n| visited.push(n))
+            .expect("bfs_visit resulted in an error unexpectedly");
+        assert_eq!(Ok(visited), TEST_GRAPH_UNWEIGHTED.bfs(source));
+    }
+    let mut count = 0;
+    TEST_GRAPH_WEIGHTED
+        .bfs_visit(0, |_| count += 1)
+        .expect("bfs_visit(0) resulted in an error unexpectedly");
+    assert_eq!(count, 10);
+    assert!(matches!(
+        TEST_GRAPH_UNWEIGHTED.bfs_visit(6, |_| {}),
+        Err(GraphError::OutOfBoundsNode { node: 6 })
+    ));
+}
+
+#[test]
+fn bfs_find_test() {
+    assert_eq!(TEST_GRAPH_UNWEIGHTED.bfs_find(0, |n| n > 3), Ok(Some(5)));
+    assert_eq!(TEST_GRAPH_UNWEIGHTED.bfs_find(0, |n| n == 0), Ok(Some(0)));
+    // Nodes 3 and 4 are not reachable from node 0.
+    assert_eq!(TEST_GRAPH_UNWEIGHTED.bfs_find(0, |n| n == 3), Ok(None));
+    assert!(matches!(
+        TEST_GRAPH_UNWEIGHTED.bfs_find(6, |_| true),
+        Err(GraphError::OutOfBoundsNode { node: 6 })
+    ));
+}
+
+#[test]
+fn dimacs_round_trip() {
+    let dimacs = TEST_GRAPH_WEIGHTED.to_dimacs();
+    let mut lines = dimacs.lines();
+    assert_eq!(lines.next(), Some("p sp 15 23"));
+    // The first edge 0 -(4)-> 1 is written with 1-based ids.
+    assert_eq!(lines.next(), Some("a 1 2 4"));
+    let parsed = Graph::from_dimacs(&dimacs).expect("from_dimacs failed unexpectedly");
+    assert_eq!(parsed, *TEST_GRAPH_WEIGHTED);
+    let with_comments = "c a comment\n\np sp 2 1\na 2 1 7\n";
+    assert_eq!(
+        Graph::from_dimacs(with_comments),
+        Ok(Graph::new(vec![vec![], vec![(0, Weighted(7))]]))
+    );
+}
+
+#[test]
+fn dimacs_parse_errors() {
+    assert_eq!(
+        Graph::from_dimacs("a 1 2 3\n"),
+        Err(ParseError::MissingHeader)
+    );
+    assert_eq!(
+        Graph::from_dimacs("p sp 2 1\na 1 3 3\n"),
+        Err(ParseError::NodeOutOfRange { line: 2, node: 3 })
+    );
+    assert_eq!(
+        Graph::from_dimacs("p sp 2 1\na 0 1 3\n"),
+        Err(ParseError::NodeOutOfRange { line: 2, node: 0 })
+    );
+    assert_eq!(
+        Graph::from_dimacs("p sp 2 1\na 1 2 x\n"),
+        Err(ParseError::InvalidLine { line: 2 })
+    );
+    assert_eq!(
+        Graph::from_dimacs("p sp 2 2\na 1 2 3\n"),
+        Err(ParseError::EdgeCountMismatch {
+            expected: 2,
+            found: 1
+        })
+    );
+}
+
+#[test]
+fn dimacs_rejects_oversized_headers() {
+    assert_eq!(
+        Graph::from_dimacs("p sp 4000000000 0\n"),
+        Err(ParseError::TooManyNodes {
+            line: 1,
+            nodes: 4_000_000_000
+        })
+    );
+    assert_eq!(
+        Graph::from_dimacs(&format!("p sp {} 0\n", Graph::MAX_DIMACS_NODES + 1)),
+        Err(ParseError::TooManyNodes {
+            line: 1,
+            nodes: Graph::MAX_DIMACS_NODES + 1
+        })
+    );
+    assert_eq!(
+        Graph::from_dimacs("c big\np sp 99999999999 0\n"),
+        Err(ParseError::InvalidLine { line: 2 })
+    );
+    assert_eq!(
+        Graph::from_dimacs("p sp 2 99999999999999999999999\n"),
+        Err(ParseError::InvalidLine { line: 1 })
+    );
+}
+
+#[test]
+fn dimacs_rejects_extra_arcs() {
+    assert_eq!(
+        Graph::from_dimacs("p sp 2 1\na 1 2 3\na 2 1 3\n"),
+        Err(ParseError::EdgeCountMismatch {
+            expected: 1,
+            found: 2
+        })
+    );
+}
+
+#[test]
+fn graphml_export_test() {
+    let unweighted = Graph::new(vec![vec![(1, Unweighted(()))], vec![]]);
+    let xml = unweighted.to_graphml();
+    assert!(xml.starts_with("<?xml"));
+    assert!(xml.contains("<node id=\"n0\"/>"));
+    assert!(xml.contains("<node id=\"n1\"/>"));
+    assert!(xml.contains("<edge source=\"n0\" target=\"n1\"/>"));
+    assert!(!xml.contains("<key"));
+    assert!(xml.trim_end().ends_with("</graphml>"));
+    let weighted = Graph::new(vec![vec![(1, Weighted(4))], vec![]]);
+    let xml = weighted.to_graphml();
+    assert!(xml.contains("<key id=\"weight\" for=\"edge\""));
+    assert!(xml.contains(
+        "<edge source=\"n0\" target=\"n1\">\n      <data key=\"weight\">4</data>\n    </edge>"
+    ));
+    let xml = TEST_GRAPH_WEIGHTED.to_graphml();
+    assert_eq!(xml.matches("<node ").count(), 15);
+    assert_eq!(xml.matches("<edge ").count(), 23);
+}
+
+#[test]
+fn average_shortest_path_length_test() {
+    // Undirected path 0 -(1)- 1 -(2)- 2.
+    let g = Graph::new(vec![
+        vec![(1, Weighted(1))],
+        vec![(0, Weighted(1)), (2, Weighted(2))],
+        vec![(1, Weighted(2))],
+    ]);
+    // (1 + 3 + 1 + 2 + 3 + 2) / 6
+    assert_eq!(g.average_shortest_path_length(), Ok(Some(2.0)));
+    let isolated = Graph::new(vec![vec![], vec![]]);
+    assert_eq!(isolated.average_shortest_path_length(), Ok(None));
+}
+
+#[test]
+fn undirected_edges_counts_each_edge_once() {
+    let g: Graph<Weighted> = Graph::random_graph(20, 0.5, false);
+    assert_eq!(g.undirected_edges().count() * 2, g.edges().count());
+    assert!(g.undirected_edges().all(|(u, v, _)| u <= v));
+    let mut edges: Vec<_> = Graph::cycle(4)
+        .undirected_edges()
+        .map(|(u, v, _)| (u, v))
+        .collect();
+    edges.sort();
+    assert_eq!(edges, vec![(0, 1), (0, 3), (1, 2), (2, 3)]);
+}
+
+#[test]
+fn is_valid_walk_test() {
+    assert_eq!(
+        TEST_GRAPH_UNWEIGHTED.is_valid_walk(&[2, 0, 1, 5, 0]),
+        Ok(true)
+    );
+    assert_eq!(TEST_GRAPH_UNWEIGHTED.is_valid_walk(&[0, 1, 2]), Ok(false));
+    assert_eq!(TEST_GRAPH_UNWEIGHTED.is_valid_walk(&[]), Ok(true));
+    assert_eq!(TEST_GRAPH_UNWEIGHTED.is_valid_walk(&[3]), Ok(true));
+    assert!(matches!(
+        TEST_GRAPH_UNWEIGHTED.is_valid_walk(&[0, 1, 6]),
+        Err(GraphError::OutOfBoundsNode { node: 6 })
+    ));
+}
+
+#[test]
+fn barabasi_albert_test() {
+    for m in [1, 2, 3] {
+        let g = Graph::barabasi_albert(50, m);
+        assert_eq!(g.node_count(), 50);
+        assert_eq!(g.bfs(0).map(|v| v.len()), Ok(50));
+        // Every node added after the seed clique brings exactly m edges.
+        let seed_edges = (m * (m - 1) / 2) as usize;
+        assert_eq!(
+            g.undirected_edges().count(),
+            seed_edges + ((50 - m) * m) as usize
+        );
+    }
+    assert_eq!(Graph::barabasi_albert(3, 5), Graph::complete(3));
+}
+
+#[test]
+fn watts_strogatz_test() {
+    let lattice = Graph::watts_strogatz(20, 4, 0.0);
+    assert_eq!(lattice.node_count(), 20);
+    for node in 0..20 {
+        assert_eq!(lattice.adjacency()[node].len(), 4);
+    }
+    assert!(lattice.edges().all(|(u, v, _)| {
+        let d = u.abs_diff(v);
+        d.min(20 - d) <= 2
+    }));
+    // Rewiring moves edges around but never creates or destroys any.
+    let rewired = Graph::watts_strogatz(20, 4, 1.0);
+    assert_eq!(rewired.undirected_edges().count(), 40);
+    assert!(rewired.edges().all(|(u, v, _)| u != v));
+}
+
+#[test]
+fn triangle_count_test() {
+    assert_eq!(Graph::complete(4).triangle_count(), 4);
+    assert_eq!(Graph::complete(5).triangle_count(), 10);
+    assert_eq!(Graph::grid(1, 5, false).triangle_count(), 0);
+    // Directed triangle: directions are ignored.
+    let directed = Graph::new(vec![
+        vec![(1, Unweighted(()))],
+        vec![(2, Unweighted(()))],
+        vec![(0, Unweighted(()))],
+    ]);
+    assert_eq!(directed.triangle_count(), 1);
+}
+
+#[test]
+fn clustering_coefficient_test() {
+    let triangle = Graph::complete(3);
+    for node in 0..3 {
+        assert_eq!(triangle.clustering_coefficient(node), Ok(1.0));
+    }
+    assert_eq!(triangle.average_clustering_coefficient(), 1.0);
+    // Triangle 0-1-2 with a pendant node 3 attached to 0.
+    let g = Graph::new(vec![
+        vec![
+            (1, Unweighted(())),
+            (2, Unweighted(())),
+            (3, Unweighted(())),
+        ],
+        vec![(0, Unweighted(())), (2, Unweighted(()))],
+        vec![(0, Unweighted(())), (1, Unweighted(()))],
+        vec![(0, Unweighted(()))],
+    ]);
+    assert_eq!(g.clustering_coefficient(0), Ok(1.0 / 3.0));
+    assert_eq!(g.clustering_coefficient(3), Ok(0.0));
+    assert_eq!(
+        g.average_clustering_coefficient(),
+        (1.0 / 3.0 + 1.0 + 1.0 + 0.0) / 4.0
+    );
+    assert!(matches!(
+        g.clustering_coefficient(4),
+        Err(GraphError::OutOfBoundsNode { node: 4 })
+    ));
+}
+
+#[test]
+fn maximal_independent_set_test() {
+    let path = Graph::grid(1, 6, false);
+    let set = path.maximal_independent_set();
+    let in_set = |n: u32| set.contains(&n);
+    // Independent: no edge has both endpoints in the set.
+    assert!(path.edges().all(|(u, v, _)| !(in_set(u) && in_set(v))));
+    // Maximal: every node outside the set has a neighbour inside it.
+    for node in (0..6).filter(|&n| !in_set(n)) {
+        assert!(path.edges().any(|(u, v, _)| u == node && in_set(v)));
+    }
+    // The two degree-1 endpoints are picked first.
+    assert!(in_set(0) && in_set(5));
+}
+
+#[test]
+fn dfs_order_test() {
+    let g = Graph::new(vec![
+        vec![(1, Unweighted(())), (2, Unweighted(()))],
+        vec![(0, Unweighted(())), (3, Unweighted(()))],
+        vec![(3, Unweighted(()))],
+        vec![(1, Unweighted(())), (2, Unweighted(()))],
+    ]);
+    assert_eq!(g.dfs(0), Ok(vec![0, 1, 3, 2]));
+}
+
+#[test]
+fn dfs_large_random_graph_test() {
+    let g: Graph<Unweighted> = Graph::random_graph(1000, 0.02, true);
+    let mut bfs_result = g.bfs(0).expect("bfs(0) resulted in an error unexpectedly");
+    bfs_result.sort();
+    let mut dfs_result = g.dfs(0).expect("dfs(0) resulted in an error unexpectedly");
+    dfs_result.sort();
+    assert_eq!(bfs_result, dfs_result);
+}
+
+#[test]
+fn maximum_bipartite_matching_test() {
+    // Workers 0..4 and jobs 4..8; worker 3 can only do job 4, which worker 0
+    // also wants, and job 7 is wanted by nobody. The maximum matching has 3 edges.
+    let g = Graph::new(vec![
+        vec![(4, Unweighted(())), (5, Unweighted(()))],
+        vec![(5, Unweighted(()))],
+        vec![(5, Unweighted(())), (6, Unweighted(()))],
+        vec![(4, Unweighted(()))],
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+    ]);
+    let left = [0, 1, 2, 3];
+    let right = [4, 5, 6, 7];
+    let matching = g
+        .maximum_bipartite_matching(&left, &right)
+        .expect("matching resulted in an error unexpectedly");
+    assert_eq!(matching.len(), 3);
+    for &(l, r) in &matching {
+        assert!(left.contains(&l) && right.contains(&r));
+        assert!(g.edges().any(|(u, v, _)| u == l && v == r));
+    }
+    let mut matched: Vec<u32> = matching.iter().flat_map(|&(l, r)| [l, r]).collect();
+    matched.sort();
+    matched.dedup();
+    assert_eq!(matched.len(), 6);
+    // Edges stored from right to left count as well.
+    let reversed = Graph::new(vec![vec![], vec![(0, Weighted(1))]]);
+    assert_eq!(
+        reversed.maximum_bipartite_matching(&[0], &[1]),
+        Ok(vec![(0, 1)])
+    );
+    assert_eq!(
+        g.maximum_bipartite_matching(&[0, 1, 2], &right),
+        Err(GraphError::InvalidPartition)
+    );
+    assert_eq!(
+        g.maximum_bipartite_matching(&[0, 1, 2, 3, 4], &right),
+        Err(GraphError::InvalidPartition)
+    );
+    assert_eq!(
+        g.maximum_bipartite_matching(&left, &[4, 5, 6, 8]),
+        Err(GraphError::OutOfBoundsNode { node: 8 })
+    );
+}
+
+#[test]
+fn dijkstra_multi_source_test() {
+    let sources = [0, 10, 8];
+    let multi = TEST_GRAPH_WEIGHTED
+        .dijkstra_multi_source(&sources)
+        .expect("dijkstra_multi_source resulted in an error unexpectedly");
+    let single: Vec<Vec<Option<u32>>> = sources
+        .iter()
+        .map(|&s| {
+            TEST_GRAPH_WEIGHTED
+                .dijkstra(s)
+                .expect("dijkstra resulted in an error unexpectedly")
+        })
+        .collect();
+    for node in 0..TEST_GRAPH_WEIGHTED.node_count() {
+        let nearest = single.iter().filter_map(|d| d[node]).min();
+        assert_eq!(multi[node], nearest, "distance mismatch for node {node}");
+    }
+    assert_eq!(multi[14], None);
+    assert!(matches!(
+        TEST_GRAPH_WEIGHTED.dijkstra_multi_source(&[0, 15]),
+        Err(GraphError::OutOfBoundsNode { node: 15 })
+    ));
+}
+
+#[test]
+fn transpose_test() {
+    let g = Graph::new(vec![
+        vec![(1, Weighted(2)), (2, Weighted(3))],
+        vec![(2, Weighted(4))],
+        vec![],
+    ]);
+    let expected = Graph::new(vec![
+        vec![],
+        vec![(0, Weighted(2))],
+        vec![(0, Weighted(3)), (1, Weighted(4))],
+    ]);
+    assert_eq!(g.transpose(), expected);
+    assert_eq!(g.transpose().transpose(), g);
+}
+
+#[test]
+fn bidirectional_dijkstra_matches_dijkstra() {
+    let n = TEST_GRAPH_WEIGHTED.node_count() as u32;
+    for source in 0..n {
+        let distances = TEST_GRAPH_WEIGHTED
+            .dijkstra(source)
+            .expect("dijkstra resulted in an error unexpectedly");
+        for target in 0..n {
+            assert_eq!(
+                TEST_GRAPH_WEIGHTED.bidirectional_dijkstra(source, target),
+                Ok(distances[target as usize]),
+                "distance mismatch from {source} to {target}"
+            );
+        }
+    }
+    let g: Graph<Weighted> = Graph::random_graph(60, 0.05, true);
+    for (source, target) in [(0, 59), (3, 17), (42, 8), (10, 10)] {
+        let expected = g.dijkstra(source).map(|d| d[target as usize]);
+        assert_eq!(g.bidirectional_dijkstra(source, target), expected);
+    }
+    assert!(matches!(
+        TEST_GRAPH_WEIGHTED.bidirectional_dijkstra(0, 15),
+        Err(GraphError::OutOfBoundsNode { node: 15 })
+    ));
+}
+
+#[test]
+fn bidirectional_dijkstra_skips_overflowing_meetings() {
+    // The heavy bridge 0 -> 2 -> 1 meets the backward search at node 2 with a
+    // total that overflows, while the real shortest path 0 -> 4 -> 1 is short.
+    let heavy = 3_000_000_000;
+    let g = Graph::new(vec![
+        vec![(4, Weighted(5)), (2, Weighted(heavy)), (3, Weighted(100))],
+        vec![],
+        vec![(1, Weighted(heavy))],
+        vec![(2, Weighted(100))],
+        vec![(1, Weighted(5))],
+    ]);
+    let distances = g
+        .dijkstra(0)
+        .expect("dijkstra(0) resulted in an error unexpectedly");
+    assert_eq!(distances[1], Some(10));
+    assert_eq!(g.bidirectional_dijkstra(0, 1), Ok(Some(10)));
+    // Without the short path, the only meeting overflows.
+    let g = Graph::new(vec![
+        vec![(1, Weighted(heavy))],
+        vec![(2, Weighted(heavy))],
+        vec![],
+    ]);
+    assert!(matches!(
+        g.bidirectional_dijkstra(0, 2),
+        Err(GraphError::DistanceOverflow { .. })
+    ));
+}
+
+#[test]
+fn index_by_node_test() {
+    assert_eq!(TEST_GRAPH_WEIGHTED[0], [(1, Weighted(4)), (2, Weighted(1))]);
+    assert!(TEST_GRAPH_UNWEIGHTED[2].len() == 1);
+}
+
+#[test]
+#[should_panic]
+fn index_out_of_bounds_panics() {
+    let _ = &TEST_GRAPH_WEIGHTED[15];
+}
+
+#[test]
+fn reachable_count_test() {
+    for source in 0..6 {
+        assert_eq!(
+            TEST_GRAPH_UNWEIGHTED.reachable_count(source),
+            TEST_GRAPH_UNWEIGHTED.bfs(source).map(|v| v.len())
+        );
+    }
+    assert_eq!(TEST_GRAPH_WEIGHTED.reachable_count(10), Ok(4));
+    assert!(matches!(
+        TEST_GRAPH_UNWEIGHTED.reachable_count(6),
+        Err(GraphError::OutOfBoundsNode { node: 6 })
+    ));
+}
+
+#[test]
+fn spfa_matches_bellman_ford() {
+    let g = Graph::new(vec![
+        vec![(1, SignedWeighted(4)), (2, SignedWeighted(5))],
+        vec![(3, SignedWeighted(-3))],
+        vec![(1, SignedWeighted(-2)), (3, SignedWeighted(4))],
+        vec![(4, SignedWeighted(2))],
+        vec![],
+        vec![(0, SignedWeighted(1))],
+    ]);
+    let expected = vec![Some(0), Some(3), Some(5), Some(0), Some(2), None];
+    assert_eq!(g.bellman_ford(0), Ok(expected.clone()));
+    assert_eq!(g.spfa(0), Ok(expected));
+    // The weighted sample has only non-negative weights, so both must also
+    // agree with Dijkstra.
+    let sample = Graph::new(
+        TEST_GRAPH_WEIGHTED
+            .adjacency()
+            .iter()
+            .map(|edges| {
+                edges
+                    .iter()
+                    .map(|&(v, w)| (v, SignedWeighted(w.0 as i64)))
+                    .collect()
+            })
+            .collect(),
+    );
+    for source in [0, 10] {
+        let dijkstra: Vec<Option<i64>> = TEST_GRAPH_WEIGHTED
+            .dijkstra(source)
+            .expect("dijkstra resulted in an error unexpectedly")
+            .into_iter()
+            .map(|d| d.map(i64::from))
+            .collect();
+        assert_eq!(sample.bellman_ford(source), Ok(dijkstra.clone()));
+        assert_eq!(sample.spfa(source), Ok(dijkstra));
+    }
+}
+
+#[test]
+fn negative_cycle_detection() {
+    let g = Graph::new(vec![
+        vec![(1, SignedWeighted(1))],
+        vec![(2, SignedWeighted(-1))],
+        vec![(1, SignedWeighted(-1))],
+        vec![],
+    ]);
+    assert!(matches!(
+        g.bellman_ford(0),
+        Err(GraphError::NegativeCycle { .. })
+    ));
+    assert!(matches!(g.spfa(0), Err(GraphError::NegativeCycle { .. })));
+    // The cycle is not reachable from node 3.
+    assert_eq!(g.spfa(3), Ok(vec![None, None, None, Some(0)]));
+    assert!(matches!(
+        g.spfa(4),
+        Err(GraphError::OutOfBoundsNode { node: 4 })
+    ));
+}
+
+#[test]
+fn spfa_dense_graph_without_negative_cycle() {
+    // Nodes 0 to 2 form a chain, each linked to every middle node 3 to 7, which
+    // all lead to the sink 8. The sink improves once per middle node in every
+    // round, more than V times overall, although there is no negative cycle.
+    let to_middles = (3..8).map(|middle| (middle, SignedWeighted(0)));
+    let g = Graph::new(vec![
+        std::iter::once((1, SignedWeighted(-5)))
+            .chain(to_middles.clone())
+            .collect(),
+        std::iter::once((2, SignedWeighted(-5)))
+            .chain(to_middles.clone())
+            .collect(),
+        to_middles.collect(),
+        vec![(8, SignedWeighted(0))],
+        vec![(8, SignedWeighted(-1))],
+        vec![(8, SignedWeighted(-2))],
+        vec![(8, SignedWeighted(-3))],
+        vec![(8, SignedWeighted(-4))],
+        vec![],
+    ]);
+    let mut expected = vec![Some(0), Some(-5)];
+    expected.extend([Some(-10); 6]);
+    expected.push(Some(-14));
+    assert_eq!(g.bellman_ford(0), Ok(expected.clone()));
+    assert_eq!(g.spfa(0), Ok(expected));
+}
+
+#[test]
+fn signed_distance_overflow() {
+    let g = Graph::new(vec![
+        vec![(1, SignedWeighted(i64::MIN + 1))],
+        vec![(2, SignedWeighted(-2))],
+        vec![],
+    ]);
+    let overflow = GraphError::DistanceOverflow {
+        node_from: 1,
+        node_to: 2,
+        current_distance: i64::MIN + 1,
+        edge_weight: -2,
+    };
+    assert_eq!(g.bellman_ford(0), Err(overflow));
+    assert!(matches!(
+        g.spfa(0),
+        Err(GraphError::DistanceOverflow { node_from: 1, .. })
+    ));
+}
+
+#[test]
+fn validate_weights_test() {
+    assert_eq!(TEST_GRAPH_WEIGHTED.validate_weights(), Ok(()));
+    let g = Graph::new(vec![vec![(1, Weighted(3))], vec![(2, Weighted(0))], vec![]]);
+    assert_eq!(
+        g.validate_weights(),
+        Err(GraphError::ZeroWeight {
+            node_from: 1,
+            node_to: 2
+        })
+    );
+}
+
+#[test]
+fn bridges_test() {
+    // Two triangles joined by the bridge 2 - 3.
+    let g = Graph::new(vec![
+        vec![(1, Unweighted(())), (2, Unweighted(()))],
+        vec![(2, Unweighted(()))],
+        vec![(3, Unweighted(()))],
+        vec![(4, Unweighted(())), (5, Unweighted(()))],
+        vec![(5, Unweighted(()))],
+        vec![],
+    ]);
+    assert_eq!(g.bridges(), vec![(2, 3)]);
+    assert_eq!(
+        Graph::grid(1, 4, false).bridges(),
+        vec![(0, 1), (1, 2), (2, 3)]
+    );
+    assert!(Graph::cycle(6).bridges().is_empty());
+}
+
+#[test]
+fn is_2_edge_connected_test() {
+    assert!(Graph::cycle(5).is_2_edge_connected());
+    assert!(!Graph::grid(1, 5, false).is_2_edge_connected());
+    // Two disjoint cycles have no bridges but are not connected.
+    let two_cycles = Graph::new(vec![
+        vec![(1, Unweighted(())), (2, Unweighted(()))],
+        vec![(2, Unweighted(()))],
+        vec![],
+        vec![(4, Unweighted(())), (5, Unweighted(()))],
+        vec![(5, Unweighted(()))],
+        vec![],
+    ]);
+    assert!(!two_cycles.is_2_edge_connected());
+    assert!(Graph::<Unweighted>::new(vec![vec![]]).is_2_edge_connected());
+    assert!(!Graph::<Unweighted>::default().is_2_edge_connected());
+}
+
+#[test]
+fn route_inspection_length_test() {
+    // Undirected 4-cycle: already Eulerian.
+    let square = Graph::new(vec![
+        vec![(1, Weighted(1)), (3, Weighted(4))],
+        vec![(0, Weighted(1)), (2, Weighted(2))],
+        vec![(1, Weighted(2)), (3, Weighted(3))],
+        vec![(2, Weighted(3)), (0, Weighted(4))],
+    ]);
+    assert_eq!(square.route_inspection_length(), Ok(1 + 2 + 3 + 4));
+    // Path 0 -(2)- 1 -(5)- 2: every edge has to be walked twice.
+    let path = Graph::new(vec![
+        vec![(1, Weighted(2))],
+        vec![(0, Weighted(2)), (2, Weighted(5))],
+        vec![(1, Weighted(5))],
+    ]);
+    assert_eq!(path.route_inspection_length(), Ok(14));
+    // Square with the diagonal 0 - 2: nodes 0 and 2 are odd and the cheapest
+    // way to pair them is the diagonal itself.
+    let mut diagonal = Graph::new(square.adjacency().to_vec());
+    diagonal
+        .add_edge(0, 2, Weighted(2))
+        .and_then(|_| diagonal.add_edge(2, 0, Weighted(2)))
+        .expect("add_edge resulted in an error unexpectedly");
+    assert_eq!(diagonal.route_inspection_length(), Ok(10 + 2 + 2));
+    let disconnected = Graph::new(vec![
+        vec![(1, Weighted(1))],
+        vec![(0, Weighted(1))],
+        vec![(3, Weighted(1))],
+        vec![(2, Weighted(1))],
+    ]);
+    assert_eq!(
+        disconnected.route_inspection_length(),
+        Err(GraphError::NotConnected)
+    );
+}
+
+#[test]
+fn route_inspection_length_edge_cases() {
+    // An undirected self-loop is stored once but adds 2 to the degree, so
+    // only 0 and 1 are odd and the edge 0 - 1 is walked twice.
+    let looped = Graph::new(vec![
+        vec![(1, Weighted(2)), (0, Weighted(5))],
+        vec![(0, Weighted(2))],
+    ]);
+    assert_eq!(looped.route_inspection_length(), Ok(2 + 5 + 2));
+    // A missing half-edge leaves a single odd node that cannot be paired.
+    let half = Graph::new(vec![vec![(1, Weighted(1))], vec![]]);
+    assert_eq!(
+        half.route_inspection_length(),
+        Err(GraphError::NotUndirected)
+    );
+    // Undirected star: the centre 0 and every leaf have odd degree when the
+    // number of leaves is odd, otherwise only the leaves do.
+    let star = |leaves: u32| {
+        let mut adjacency = vec![(1..=leaves).map(|v| (v, Weighted(1))).collect()];
+        adjacency.extend((1..=leaves).map(|_| vec![(0, Weighted(1))]));
+        Graph::new(adjacency)
+    };
+    assert_eq!(
+        star(22).route_inspection_length(),
+        Err(GraphError::TooManyOddNodes {
+            count: 22,
+            limit: Graph::MAX_ROUTE_INSPECTION_ODD_NODES
+        })
+    );
+    assert_eq!(star(20).route_inspection_length(), Ok(40));
+}
+
+#[test]
+fn topological_sort_lexicographic_test() {
+    // 5 -> 2, 5 -> 0, 4 -> 0, 4 -> 1, 2 -> 3, 3 -> 1
+    let dag = Graph::new(vec![
+        vec![],
+        vec![],
+        vec![(3, Unweighted(()))],
+        vec![(1, Unweighted(()))],
+        vec![(0, Unweighted(())), (1, Unweighted(()))],
+        vec![(2, Unweighted(())), (0, Unweighted(()))],
+    ]);
+    assert_eq!(
+        dag.topological_sort_lexicographic(),
+        Ok(vec![4, 5, 0, 2, 3, 1])
+    );
+    assert_eq!(
+        Graph::cycle(3).topological_sort_lexicographic(),
+        Err(GraphError::CyclePresent)
+    );
+    assert_eq!(
+        TEST_GRAPH_WEIGHTED.topological_sort_lexicographic(),
+        Err(GraphError::CyclePresent)
+    );
+}
+
+#[test]
+fn weight_conversion_test() {
+    let weighted = TEST_GRAPH_UNWEIGHTED.to_weighted(1);
+    assert_eq!(weighted.to_unweighted(), *TEST_GRAPH_UNWEIGHTED);
+    for source in 0..6 {
+        // Hop distances computed level by level with BFS.
+        let mut hops: Vec<Option<u32>> = vec![None; 6];
+        let mut level: Vec<u32> = vec![source];
+        let mut depth = 0;
+        while !level.is_empty() {
+            let mut next = Vec::new();
+            for &node in &level {
+                if hops[node as usize].is_none() {
+                    hops[node as usize] = Some(depth);
+                    next.extend(TEST_GRAPH_UNWEIGHTED[node].iter().map(|&(n, _)| n));
+                }
+            }
+            level = next;
+            depth += 1;
+        }
+        assert_eq!(weighted.dijkstra(source), Ok(hops));
+    }
+    assert!(TEST_GRAPH_WEIGHTED.to_unweighted().edges().count() == 23);
+}
+
+#[test]
+fn filter_nodes_test() {
+    let even = TEST_GRAPH_UNWEIGHTED.filter_nodes(|n| n % 2 == 0);
+    // Old nodes 0, 2, 4 become 0, 1, 2.
+    let expected = Graph::new(vec![
+        vec![(1, Unweighted(()))],
+        vec![(0, Unweighted(()))],
+        vec![],
+    ]);
+    assert_eq!(even, expected);
+    let island = TEST_GRAPH_WEIGHTED.filter_nodes(|n| n >= 10);
+    assert_eq!(island.node_count(), 5);
+    assert_eq!(island[4], [(2, Weighted(1))]);
+    assert_eq!(TEST_GRAPH_WEIGHTED.filter_nodes(|_| false).node_count(), 0);
+}
+
+#[test]
+fn minimum_mean_cycle_test() {
+    // Cycle 0 -> 1 -> 2 -> 0 with mean (1 + 2 + 6) / 3 = 3, plus the cycle
+    // 2 -> 3 -> 2 with mean (6 + 4) / 2 = 5 and a tail 4 -> 0.
+    let g = Graph::new(vec![
+        vec![(1, Weighted(1))],
+        vec![(2, Weighted(2))],
+        vec![(0, Weighted(6)), (3, Weighted(6))],
+        vec![(2, Weighted(4))],
+        vec![(0, Weighted(1))],
+    ]);
+    let mean = g.minimum_mean_cycle().expect("the graph has cycles");
+    assert!((mean - 3.0).abs() < 1e-9, "expected 3, got {mean}");
+    let self_loop = Graph::new(vec![
+        vec![(0, Weighted(2)), (1, Weighted(1))],
+        vec![(0, Weighted(7))],
+    ]);
+    assert_eq!(self_loop.minimum_mean_cycle(), Some(2.0));
+    let dag = Graph::new(vec![
+        vec![(1, Weighted(1)), (2, Weighted(1))],
+        vec![(2, Weighted(1))],
+        vec![],
+    ]);
+    assert_eq!(dag.minimum_mean_cycle(), None);
+}
+
+#[test]
+fn path_weight_test() {
+    assert_eq!(
+        TEST_GRAPH_WEIGHTED.path_weight(&[0, 2, 1, 3, 6]),
+        Ok(Some(7))
+    );
+    assert_eq!(TEST_GRAPH_WEIGHTED.path_weight(&[0, 3]), Ok(None));
+    assert_eq!(TEST_GRAPH_WEIGHTED.path_weight(&[4]), Ok(Some(0)));
+    let parallel = Graph::new(vec![vec![(1, Weighted(5)), (1, Weighted(2))], vec![]]);
+    assert_eq!(parallel.path_weight(&[0, 1]), Ok(Some(2)));
+    assert!(matches!(
+        TEST_GRAPH_WEIGHTED.path_weight(&[0, 15]),
+        Err(GraphError::OutOfBoundsNode { node: 15 })
+    ));
+}
+
+#[test]
+fn path_weight_overflow_test() {
+    let heavy = Graph::new(vec![
+        vec![(1, Weighted(u32::MAX - 1))],
+        vec![(2, Weighted(2))],
+        vec![],
+    ]);
+    assert_eq!(
+        heavy.path_weight(&[0, 1, 2]),
+        Err(GraphError::DistanceOverflow {
+            node_from: 1,
+            node_to: 2,
+            current_distance: i64::from(u32::MAX - 1),
+            edge_weight: 2,
+        })
+    );
+}
+
+#[test]
+fn insert_edge_pub_test() {
+    let mut g: Graph<Weighted> = Graph::new(vec![vec![], vec![], vec![]]);
+    g.insert_edge_pub(0, 1, Weighted(3), false)
+        .expect("insert_edge_pub(0, 1) resulted in an error unexpectedly");
+    g.insert_edge_pub(1, 2, Weighted(5), true)
+        .expect("insert_edge_pub(1, 2) resulted in an error unexpectedly");
+    let expected = Graph::new(vec![
+        vec![(1, Weighted(3))],
+        vec![(0, Weighted(3)), (2, Weighted(5))],
+        vec![],
+    ]);
+    assert_eq!(g, expected);
+    assert_eq!(
+        g.insert_edge_pub(2, 3, Weighted(1), false),
+        Err(GraphError::OutOfBoundsNode { node: 3 })
+    );
+    assert_eq!(g, expected);
+}
+
+#[test]
+fn nodes_at_distance_test() {
+    assert_eq!(TEST_GRAPH_WEIGHTED.nodes_at_distance(0, 7), Ok(vec![6]));
+    assert_eq!(TEST_GRAPH_WEIGHTED.nodes_at_distance(0, 0), Ok(vec![0]));
+    assert_eq!(TEST_GRAPH_WEIGHTED.nodes_at_distance(0, 2), Ok(vec![]));
+    assert!(matches!(
+        TEST_GRAPH_WEIGHTED.nodes_at_distance(15, 1),
+        Err(GraphError::OutOfBoundsNode { node: 15 })
+    ));
+}
+
+#[test]
+fn disjoint_union_and_join_test() {
+    let triangle = Graph::complete(3);
+    let union = triangle.disjoint_union(&triangle);
+    assert_eq!(union.node_count(), 6);
+    assert_eq!(union.edges().count(), 12);
+    assert!(!union.is_connected());
+    let joined = triangle.join(&triangle, &[(2, 0, Unweighted(()))]);
+    assert_eq!(joined.node_count(), 6);
+    assert_eq!(joined[2].last(), Some(&(3, Unweighted(()))));
+    assert!(joined.is_connected());
+    assert_eq!(joined.bridges(), vec![(2, 3)]);
+}
+
+#[test]
+#[should_panic]
+fn join_out_of_bounds_bridge_panics() {
+    let triangle = Graph::complete(3);
+    triangle.join(&triangle, &[(0, 3, Unweighted(()))]);
+}
+
+#[test]
+fn degree_histogram_test() {
+    let histogram = star_graph(5).degree_histogram();
+    assert_eq!(histogram, vec![0, 5, 0, 0, 0, 1]);
+    // Nodes 3 and 4 only point at each other, node 0 has three neighbours.
+    assert_eq!(TEST_GRAPH_UNWEIGHTED.degree_histogram(), vec![0, 3, 2, 1]);
+    assert!(Graph::<Unweighted>::default().degree_histogram().is_empty());
+}
+
+#[test]
+fn shrink_to_fit_test() {
+    let mut g: Graph<Weighted> = Graph::default();
+    for _ in 0..5 {
+        g.add_node();
+    }
+    for i in 0..17 {
+        g.add_edge(0, i % 5, Weighted(i))
+            .expect("add_edge resulted in an error unexpectedly");
+    }
+    let before = g.adjacency()[0].capacity();
+    assert!(before > 17);
+    g.shrink_to_fit();
+    assert!(g.adjacency()[0].capacity() < before);
+    assert_eq!(g.adjacency().len(), 5);
+    assert_eq!(g.edges().count(), 17);
+}
+
+#[test]
+fn unreachable_from_test() {
+    assert_eq!(
+        TEST_GRAPH_WEIGHTED.unreachable_from(0),
+        Ok(vec![10, 11, 12, 13, 14])
+    );
+    assert_eq!(
+        TEST_GRAPH_WEIGHTED.unreachable_from(14),
+        Ok(vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9])
+    );
+    assert!(matches!(
+        TEST_GRAPH_WEIGHTED.unreachable_from(15),
+        Err(GraphError::OutOfBoundsNode { node: 15 })
+    ));
+}
+
+#[test]
+fn retain_edges_test() {
+    let mut g = Graph::new(TEST_GRAPH_WEIGHTED.adjacency().to_vec());
+    g.retain_edges(|_, _, w| w.0 <= 5);
+    let survivors: Vec<_> = g.edges().map(|(u, v, w)| (u, v, w.0)).collect();
+    assert_eq!(
+        survivors,
+        vec![
+            (0, 1, 4),
+            (0, 2, 1),
+            (1, 3, 1),
+            (2, 1, 2),
+            (2, 3, 5),
+            (3, 6, 3),
+            (4, 6, 2),
+            (4, 7, 3),
+            (5, 4, 2),
+            (6, 9, 4),
+            (7, 6, 3),
+            (7, 9, 2),
+            (8, 7, 1),
+            (9, 5, 1),
+            (10, 11, 3),
+            (11, 12, 4),
+            (12, 13, 2),
+            (14, 12, 1),
+        ]
+    );
+    g.retain_edges(|u, v, _| u < v);
+    assert!(g.edges().all(|(u, v, _)| u < v));
+    assert_eq!(g.node_count(), 15);
+}
+
+#[test]
+fn map_weights_test() {
+    let doubled = TEST_GRAPH_WEIGHTED.map_weights(|w| Weighted(w.0 * 2));
+    for source in [0, 10] {
+        let expected: Vec<Option<u32>> = TEST_GRAPH_WEIGHTED
+            .dijkstra(source)
+            .expect("dijkstra resulted in an error unexpectedly")
+            .into_iter()
+            .map(|d| d.map(|d| d * 2))
+            .collect();
+        assert_eq!(doubled.dijkstra(source), Ok(expected));
+    }
+    let signed = TEST_GRAPH_WEIGHTED.map_weights(|w| SignedWeighted(-(w.0 as i64)));
+    assert_eq!(
+        signed[0],
+        [(1, SignedWeighted(-4)), (2, SignedWeighted(-1))]
+    );
+}
+
+#[test]
+fn is_symmetric_test() {
+    let symmetric = Graph::new(vec![
+        vec![(1, Weighted(3)), (2, Weighted(1))],
+        vec![(0, Weighted(3))],
+        vec![(0, Weighted(1)), (2, Weighted(9))],
+    ]);
+    assert!(symmetric.is_symmetric());
+    let different_weights = Graph::new(vec![vec![(1, Weighted(3))], vec![(0, Weighted(4))]]);
+    assert!(!different_weights.is_symmetric());
+    assert!(!TEST_GRAPH_WEIGHTED.is_symmetric());
+    let g: Graph<Weighted> = Graph::random_graph(20, 0.5, false);
+    assert!(g.is_symmetric());
+}
+
+#[test]
+fn is_isolated_test() {
+    // Node 1 only has an incoming edge, node 2 has no edges at all.
+    let g = Graph::new(vec![vec![(1, Unweighted(()))], vec![], vec![]]);
+    assert_eq!(g.is_isolated(0), Ok(false));
+    assert_eq!(g.is_isolated(1), Ok(false));
+    assert_eq!(g.is_isolated(2), Ok(true));
+    assert_eq!(g.bfs(2), Ok(vec![2]));
+    assert!(matches!(
+        g.is_isolated(3),
+        Err(GraphError::OutOfBoundsNode { node: 3 })
+    ));
+}
+
+#[test]
+fn dijkstra_result_test() {
+    let paths = TEST_GRAPH_WEIGHTED
+        .dijkstra_result(0)
+        .expect("dijkstra_result(0) resulted in an error unexpectedly");
+    assert_eq!(paths.source(), 0);
+    assert_eq!(paths.distance_to(6), Some(7));
+    assert_eq!(paths.distance_to(10), None);
+    assert_eq!(paths.distance_to(15), None);
+    assert!(paths.is_reachable(9));
+    assert!(!paths.is_reachable(14));
+    assert_eq!(
+        Ok(paths.distances().to_vec()),
+        TEST_GRAPH_WEIGHTED.dijkstra(0)
+    );
+    assert!(matches!(
+        TEST_GRAPH_WEIGHTED.dijkstra_result(15),
+        Err(GraphError::OutOfBoundsNode { node: 15 })
+    ));
+}
+
+#[test]
+fn in_degrees_test() {
+    let path = Graph::new(vec![
+        vec![(1, Unweighted(()))],
+        vec![(2, Unweighted(()))],
+        vec![(3, Unweighted(()))],
+        vec![],
+    ]);
+    assert_eq!(path.in_degrees(), vec![0, 1, 1, 1]);
+    assert_eq!(
+        TEST_GRAPH_WEIGHTED.in_degrees(),
+        vec![0, 2, 1, 2, 2, 2, 3, 2, 1, 3, 1, 1, 2, 1, 0]
+    );
+}
+
+#[test]
+fn bfs_shortest_path_test() {
+    assert_eq!(
+        TEST_GRAPH_UNWEIGHTED.bfs_shortest_path(2, 5),
+        Ok(Some(vec![2, 0, 5]))
+    );
+    assert_eq!(
+        TEST_GRAPH_UNWEIGHTED.bfs_shortest_path(1, 1),
+        Ok(Some(vec![1]))
+    );
+    assert_eq!(TEST_GRAPH_UNWEIGHTED.bfs_shortest_path(0, 3), Ok(None));
+    assert_eq!(
+        TEST_GRAPH_UNWEIGHTED.bfs_shortest_path(4, 3),
+        Ok(Some(vec![4, 3]))
+    );
+    // The 3 x 3 grid has a 4-hop route between opposite corners.
+    let grid = Graph::grid(3, 3, false);
+    let path = grid
+        .bfs_shortest_path(0, 8)
+        .expect("bfs_shortest_path resulted in an error unexpectedly")
+        .expect("opposite corners of a grid are connected");
+    assert_eq!(path.len(), 5);
+    assert_eq!(grid.is_valid_walk(&path), Ok(true));
+    assert!(matches!(
+        TEST_GRAPH_UNWEIGHTED.bfs_shortest_path(0, 6),
+        Err(GraphError::OutOfBoundsNode { node: 6 })
+    ));
+}
+
+#[test]
+fn random_graph_with_stats_test() {
+    let (g, num_edges) = Graph::random_graph_with_stats(30, 0.3, false);
+    assert_eq!(num_edges, g.undirected_edges().count());
+    assert_eq!(num_edges * 2, g.edges().count());
+    let (g, num_edges) = Graph::random_graph_with_stats(30, 0.3, true);
+    assert_eq!(num_edges, g.edges().count());
+    let (_, num_edges) = Graph::random_graph_with_stats(10, 1.0, false);
+    assert_eq!(num_edges, 45);
+}
+
+#[test]
+fn k_core_test() {
+    // K4 on nodes 0..4, node 4 attached to 0 and 1, node 5 hanging off 4.
+    let mut g = Graph::complete(4);
+    g.add_node();
+    g.add_node();
+    for (u, v) in [(4, 0), (4, 1), (5, 4)] {
+        g.insert_edge_pub(u, v, Unweighted(()), false)
+            .expect("insert_edge_pub resulted in an error unexpectedly");
+    }
+    assert_eq!(g.k_core(3), vec![0, 1, 2, 3]);
+    assert_eq!(g.k_core(2), vec![0, 1, 2, 3, 4]);
+    assert_eq!(g.k_core(1), vec![0, 1, 2, 3, 4, 5]);
+    assert!(g.k_core(4).is_empty());
+    assert_eq!(g.core_number(), vec![3, 3, 3, 3, 2, 1]);
+    assert_eq!(Graph::cycle(5).core_number(), vec![2; 5]);
+}
+
+#[test]
+fn shortest_path_tree_test() {
+    let tree = TEST_GRAPH_WEIGHTED
+        .shortest_path_tree(0)
+        .expect("shortest_path_tree(0) resulted in an error unexpectedly");
+    let reachable = TEST_GRAPH_WEIGHTED
+        .reachable_count(0)
+        .expect("reachable_count(0) resulted in an error unexpectedly");
+    assert_eq!(tree.node_count(), TEST_GRAPH_WEIGHTED.node_count());
+    assert!(tree.edges().count() < reachable);
+    assert!(tree.topological_sort_lexicographic().is_ok());
+    // Distances along the tree are the original shortest distances.
+    assert_eq!(tree.dijkstra(0), TEST_GRAPH_WEIGHTED.dijkstra(0));
+    for (u, v, w) in tree.edges() {
+        assert!(TEST_GRAPH_WEIGHTED[u].contains(&(v, *w)));
+    }
+    assert!(tree.is_isolated(12).expect("node 12 exists"));
+    assert!(matches!(
+        TEST_GRAPH_WEIGHTED.shortest_path_tree(15),
+        Err(GraphError::OutOfBoundsNode { node: 15 })
+    ));
+}
+
+#[test]
+fn edge_betweenness_test() {
+    // Barbell: two triangles 0-1-2 and 3-4-5 joined by the edge 2 - 3.
+    let triangle = Graph::complete(3);
+    let mut barbell = triangle.disjoint_union(&triangle);
+    barbell
+        .insert_edge_pub(2, 3, Unweighted(()), false)
+        .expect("insert_edge_pub(2, 3) resulted in an error unexpectedly");
+    let scores = barbell.edge_betweenness();
+    assert_eq!(scores.len(), 7);
+    let &(bridge, bridge_score) = scores
+        .iter()
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .expect("the barbell has edges");
+    assert_eq!(bridge, (2, 3));
+    // All 3 * 3 pairs across the bridge use it.
+    assert!((bridge_score - 9.0).abs() < 1e-9);
+    // On a path 0 - 1 - 2 the two edges each carry two pairs.
+    assert_eq!(
+        Graph::grid(1, 3, false).edge_betweenness(),
+        vec![((0, 1), 2.0), ((1, 2), 2.0)]
+    );
+}
+
+#[test]
+fn period_test() {
+    let directed_triangle = Graph::new(vec![
+        vec![(1, Unweighted(()))],
+        vec![(2, Unweighted(()))],
+        vec![(0, Unweighted(()))],
+    ]);
+    assert_eq!(directed_triangle.period(), Some(3));
+    let with_self_loop = Graph::new(vec![
+        vec![(1, Unweighted(()))],
+        vec![(2, Unweighted(()))],
+        vec![(0, Unweighted(())), (2, Unweighted(()))],
+    ]);
+    assert_eq!(with_self_loop.period(), Some(1));
+    // Undirected bipartite graphs only have even cycles.
+    assert_eq!(Graph::cycle(6).period(), Some(2));
+    assert_eq!(Graph::cycle(5).period(), Some(1));
+    // Not strongly connected.
+    assert_eq!(TEST_GRAPH_WEIGHTED.period(), None);
+    assert_eq!(Graph::<Unweighted>::new(vec![vec![]]).period(), None);
+}
+
+#[test]
+fn random_graph_checked_test() {
+    for probability in [1.5, -0.1, f64::NAN] {
+        assert_eq!(
+            Graph::<Weighted>::random_graph_checked(10, probability, true),
+            Err(GraphError::InvalidProbability)
+        );
+    }
+    let g = Graph::<Unweighted>::random_graph_checked(10, 1.0, false)
+        .expect("probability 1.0 is valid");
+    assert_eq!(g.undirected_edges().count(), 45);
+    let g =
+        Graph::<Unweighted>::random_graph_checked(10, 0.0, true).expect("probability 0.0 is valid");
+    assert_eq!(g.edges().count(), 0);
+}
+
+#[test]
+fn random_graph_sampled_test() {
+    let g = Graph::random_graph_sampled(15, 0.5, true, |_| 7);
+    assert!(g.edges().all(|(_, _, w)| *w == Weighted(7)));
+    // One sample per undirected edge keeps both half-edges in sync.
+    let mut calls = 0;
+    let g = Graph::random_graph_sampled(15, 0.5, false, |rng| {
+        calls += 1;
+        rng.random_range(1..=1000)
+    });
+    assert_eq!(calls, g.undirected_edges().count());
+    assert!(g.is_symmetric());
+}
+
+#[test]
+fn transpose_in_place_test() {
+    let mut g = Graph::new(TEST_GRAPH_WEIGHTED.adjacency().to_vec());
+    g.transpose_in_place();
+    assert_eq!(g, TEST_GRAPH_WEIGHTED.transpose());
+    g.transpose_in_place();
+    assert_eq!(g, *TEST_GRAPH_WEIGHTED);
+}
+
+#[test]
+fn max_degree_node_test() {
+    assert_eq!(star_graph(5).max_degree_node(), Some((0, 5)));
+    assert_eq!(
+        Graph::<Unweighted>::cycle(4).max_degree_node(),
+        Some((0, 2))
+    );
+    assert_eq!(Graph::<Unweighted>::default().max_degree_node(), None);
+}
+
+#[test]
+fn classify_edges_test() {
+    // 0 -> 1 -> 2 -> 0 closes a cycle, 0 -> 2 skips ahead and 3 -> 1 enters
+    // a finished subtree.
+    let graph: Graph<Unweighted> = Graph::new(vec![
+        vec![(1, Unweighted(())), (2, Unweighted(()))],
+        vec![(2, Unweighted(()))],
+        vec![(0, Unweighted(()))],
+        vec![(1, Unweighted(()))],
+    ]);
+    assert_eq!(
+        graph.classify_edges(),
+        vec![
+            ((0, 1), EdgeKind::Tree),
+            ((1, 2), EdgeKind::Tree),
+            ((2, 0), EdgeKind::Back),
+            ((0, 2), EdgeKind::Forward),
+            ((3, 1), EdgeKind::Cross),
+        ]
+    );
+}
+
+#[test]
+fn widest_path_test() {
+    // 0 -> 1 -> 3 is short but narrow, 0 -> 2 -> 3 is long but wide.
+    let graph: Graph<Weighted> = Graph::new(vec![
+        vec![(1, Weighted(2)), (2, Weighted(8))],
+        vec![(3, Weighted(9))],
+        vec![(3, Weighted(5))],
+        vec![],
+    ]);
+    assert_eq!(
+        graph
+            .widest_path(0, 3)
+            .expect("widest_path(0, 3) resulted in an error unexpectedly"),
+        Some((5, vec![0, 2, 3]))
+    );
+    assert_eq!(
+        graph
+            .widest_path(3, 0)
+            .expect("widest_path(3, 0) resulted in an error unexpectedly"),
+        None
+    );
+    assert_eq!(
+        graph
+            .widest_path(1, 1)
+            .expect("widest_path(1, 1) resulted in an error unexpectedly"),
+        Some((u32::MAX, vec![1]))
+    );
+    assert!(matches!(
+        graph.widest_path(0, 4),
+        Err(GraphError::OutOfBoundsNode { node: 4 })
+    ));
+}
+
+#[test]
+fn second_shortest_distance_test() {
+    // 0 -> 1 -> 3 costs 3, 0 -> 2 -> 3 costs 5 and 0 -> 3 costs 3 again.
+    let graph: Graph<Weighted> = Graph::new(vec![
+        vec![(1, Weighted(1)), (2, Weighted(2)), (3, Weighted(3))],
+        vec![(3, Weighted(2))],
+        vec![(3, Weighted(3))],
+        vec![],
+    ]);
+    assert_eq!(
+        graph
+            .second_shortest_distance(0, 3)
+            .expect("second_shortest_distance(0, 3) resulted in an error unexpectedly"),
+        Some(5)
+    );
+    assert_eq!(
+        graph
+            .second_shortest_distance(0, 1)
+            .expect("second_shortest_distance(0, 1) resulted in an error unexpectedly"),
+        None
+    );
+    assert!(matches!(
+        graph.second_shortest_distance(4, 0),
+        Err(GraphError::OutOfBoundsNode { node: 4 })
+    ));
+}
+
+#[test]
+fn cut_size_test() {
+    let rest: Vec<u32> = (3..15).collect();
+    assert_eq!(
+        TEST_GRAPH_WEIGHTED
+            .cut_size(&[0, 1, 2], &rest)
+            .expect("cut_size(&[0, 1, 2], &rest) resulted in an error unexpectedly"),
+        4
+    );
+    assert_eq!(
+        TEST_GRAPH_UNWEIGHTED
+            .cut_size(&[0, 1, 2], &[3, 4, 5])
+            .expect("cut_size(&[0, 1, 2], &[3, 4, 5]) resulted in an error unexpectedly"),
+        3
+    );
+    assert!(matches!(
+        TEST_GRAPH_UNWEIGHTED.cut_size(&[0, 1], &[1, 2]),
+        Err(GraphError::InvalidPartition)
+    ));
+    assert!(matches!(
+        TEST_GRAPH_UNWEIGHTED.cut_size(&[0], &[6]),
+        Err(GraphError::OutOfBoundsNode { node: 6 })
+    ));
+}
+
+#[test]
+fn immediate_dominators_test() {
+    // 0 branches to 1 and 2, which meet again at 3; 3 loops back to 1 and
+    // exits to 4. Node 5 is unreachable.
+    let graph: Graph<Unweighted> = Graph::new(vec![
+        vec![(1, Unweighted(())), (2, Unweighted(()))],
+        vec![(3, Unweighted(()))],
+        vec![(3, Unweighted(()))],
+        vec![(1, Unweighted(())), (4, Unweighted(()))],
+        vec![],
+        vec![(4, Unweighted(()))],
+    ]);
+    assert_eq!(
+        graph
+            .immediate_dominators(0)
+            .expect("immediate_dominators(0) resulted in an error unexpectedly"),
+        vec![None, Some(0), Some(0), Some(0), Some(3), None]
+    );
+    assert_eq!(
+        graph
+            .immediate_dominators(1)
+            .expect("immediate_dominators(1) resulted in an error unexpectedly"),
+        vec![None, None, None, Some(1), Some(3), None]
+    );
+    assert!(matches!(
+        graph.immediate_dominators(6),
+        Err(GraphError::OutOfBoundsNode { node: 6 })
+    ));
+}
+
+#[test]
+fn spanning_tree_count_test() {
+    for n in 3..8 {
+        assert_eq!(
+            Graph::<Unweighted>::cycle(n).spanning_tree_count(),
+            n as u64
+        );
+    }
+    assert_eq!(Graph::<Unweighted>::complete(4).spanning_tree_count(), 16);
+    assert_eq!(star_graph(4).spanning_tree_count(), 1);
+    assert_eq!(TEST_GRAPH_UNWEIGHTED.spanning_tree_count(), 0);
+    // Cayley's formula: K_n has n^(n-2) spanning trees.
+    assert_eq!(
+        Graph::<Unweighted>::complete(17).spanning_tree_count(),
+        17u64.pow(15)
+    );
+    for n in [18, 20, 25, 30] {
+        assert_eq!(
+            Graph::<Unweighted>::complete(n).spanning_tree_count(),
+            u64::MAX
+        );
+    }
+}
+
+#[test]
+fn dijkstra_until_test() {
+    let full = TEST_GRAPH_WEIGHTED
+        .dijkstra(0)
+        .expect("dijkstra(0) resulted in an error unexpectedly");
+    let targets = [3, 7, 12, 1];
+    let expected: Vec<(u32, u32)> = targets
+        .iter()
+        .filter_map(|&t| full[t as usize].map(|d| (t, d)))
+        .collect();
+    assert_eq!(expected, vec![(3, 4), (7, 13), (1, 3)]);
+    assert_eq!(
+        TEST_GRAPH_WEIGHTED
+            .dijkstra_until(0, &targets)
+            .expect("dijkstra_until(0, &targets) resulted in an error unexpectedly"),
+        expected
+    );
+    assert_eq!(
+        TEST_GRAPH_WEIGHTED
+            .dijkstra_until(0, &[])
+            .expect("dijkstra_until(0, &[]) resulted in an error unexpectedly"),
+        vec![]
+    );
+    assert!(matches!(
+        TEST_GRAPH_WEIGHTED.dijkstra_until(0, &[15]),
+        Err(GraphError::OutOfBoundsNode { node: 15 })
+    ));
+}
+
+#[test]
+fn center_test() {
+    let path = |n: u32| {
+        let mut graph: Graph<Weighted> = Graph::new((0..n).map(|_| Vec::new()).collect());
+        for i in 1..n {
+            graph
+                .insert_edge_pub(i - 1, i, Weighted(2), false)
+                .expect("insert_edge_pub(i - 1, i) resulted in an error unexpectedly");
+        }
+        graph
+    };
+    assert_eq!(
+        path(5)
+            .center()
+            .expect("center() resulted in an error unexpectedly"),
+        vec![2]
+    );
+    assert_eq!(
+        path(4)
+            .center()
+            .expect("center() resulted in an error unexpectedly"),
+        vec![1, 2]
+    );
+    assert_eq!(
+        path(5)
+            .eccentricity(0)
+            .expect("eccentricity(0) resulted in an error unexpectedly"),
+        Some(8)
+    );
+    assert_eq!(
+        TEST_GRAPH_WEIGHTED
+            .eccentricity(0)
+            .expect("eccentricity(0) resulted in an error unexpectedly"),
+        None
+    );
+    assert!(matches!(
+        TEST_GRAPH_WEIGHTED.center(),
+        Err(GraphError::NotConnected)
+    ));
+}
+
+#[test]
+fn weighted_arithmetic_test() {
+    let weights = [Weighted(4), Weighted(1), Weighted(7)];
+    assert_eq!(weights.iter().copied().sum::<Weighted>(), Weighted(12));
+    assert_eq!(weights.iter().sum::<Weighted>(), Weighted(12));
+    assert_eq!(Weighted(2) + Weighted(3), Weighted(5));
+    assert_eq!(Weighted::from(9).weight(), 9);
+    assert_eq!(u32::from(Weighted(6)), 6);
+    let w: Weighted = 5.into();
+    let raw: u32 = w.into();
+    assert_eq!(raw, 5);
+    assert_eq!([].iter().sum::<Weighted>(), Weighted(0));
+}
+
+#[test]
+#[should_panic(expected = "Weighted addition overflowed")]
+fn weighted_addition_overflow_panics() {
+    let _ = Weighted(u32::MAX) + Weighted(1);
+}
+
+#[test]
+fn signed_dijkstra_rejects_negative_edges() {
+    let g = Graph::new(vec![
+        vec![(1, SignedWeighted(4)), (2, SignedWeighted(5))],
+        vec![(3, SignedWeighted(-3))],
+        vec![(1, SignedWeighted(-2)), (3, SignedWeighted(4))],
+        vec![],
+    ]);
+    assert!(g.has_negative_edges());
+    assert_eq!(
+        g.dijkstra(0),
+        Err(GraphError::NegativeEdge {
+            node_from: 1,
+            node_to: 3
+        })
+    );
+    assert_eq!(
+        g.bellman_ford(0),
+        Ok(vec![Some(0), Some(3), Some(5), Some(0)])
+    );
+    let sample = TEST_GRAPH_WEIGHTED.map_weights(|w| SignedWeighted(w.0 as i64));
+    assert!(!sample.has_negative_edges());
+    assert_eq!(sample.dijkstra(0), sample.bellman_ford(0));
+    let heavy = Graph::new(vec![
+        vec![(1, SignedWeighted(i64::MAX))],
+        vec![(2, SignedWeighted(1))],
+        vec![],
+    ]);
+    assert_eq!(
+        heavy.dijkstra(0),
+        Err(GraphError::DistanceOverflow {
+            node_from: 1,
+            node_to: 2,
+            current_distance: i64::MAX,
+            edge_weight: 1,
+        })
+    );
+}
+
+#[test]
+fn random_walk_test() {
+    let walk = |seed: u64, length: usize| {
+        TEST_GRAPH_UNWEIGHTED
+            .random_walk(0, length, &mut StdRng::seed_from_u64(seed))
+            .expect("random_walk(0, length) resulted in an error unexpectedly")
+    };
+    for seed in 0..5 {
+        let w = walk(seed, 20);
+        assert_eq!(w, walk(seed, 20));
+        assert_eq!(w.len(), 21);
+        assert_eq!(w[0], 0);
+        assert_eq!(
+            TEST_GRAPH_UNWEIGHTED.is_valid_walk(&w),
+            Ok(true),
+            "{w:?} is not a walk"
+        );
+    }
+    assert_eq!(walk(0, 0), vec![0]);
+    // The walk stops at the dead end of a single edge.
+    let path: Graph<Unweighted> = Graph::new(vec![vec![(1, Unweighted(()))], vec![]]);
+    assert_eq!(
+        path.random_walk(0, 5, &mut StdRng::seed_from_u64(1)),
+        Ok(vec![0, 1])
+    );
+    assert!(matches!(
+        path.random_walk(2, 5, &mut StdRng::seed_from_u64(1)),
+        Err(GraphError::OutOfBoundsNode { node: 2 })
+    ));
+}
+
+#[test]
+fn weighted_random_walk_prefers_heavy_edges() {
+    // From 0 the walk goes to 1 (weight 9) or 2 (weight 1) and comes back.
+    let g = Graph::new(vec![
+        vec![(1, Weighted(9)), (2, Weighted(1)), (3, Weighted(0))],
+        vec![(0, Weighted(1))],
+        vec![(0, Weighted(1))],
+        vec![(0, Weighted(1))],
+    ]);
+    let mut rng = StdRng::seed_from_u64(7);
+    let walk = g
+        .weighted_random_walk(0, 2000, &mut rng)
+        .expect("weighted_random_walk(0, 2000) resulted in an error unexpectedly");
+    assert_eq!(walk.len(), 2001);
+    assert_eq!(g.is_valid_walk(&walk), Ok(true));
+    let count = |n: u32| walk.iter().filter(|&&x| x == n).count();
+    assert!(count(1) > count(2), "{} <= {}", count(1), count(2));
+    assert_eq!(count(3), 0);
+    assert_eq!(
+        g.weighted_random_walk(0, 2000, &mut StdRng::seed_from_u64(7)),
+        Ok(walk)
+    );
+    let dead_end = Graph::new(vec![vec![(1, Weighted(0))], vec![]]);
+    assert_eq!(dead_end.weighted_random_walk(0, 3, &mut rng), Ok(vec![0]));
+    assert!(matches!(
+        dead_end.weighted_random_walk(2, 3, &mut rng),
+        Err(GraphError::OutOfBoundsNode { node: 2 })
+    ));
+}
+
+#[test]
+fn overlay_test() {
+    let a = Graph::new(vec![vec![(1, Weighted(1))], vec![(2, Weighted(2))], vec![]]);
+    let b = Graph::new(vec![vec![(2, Weighted(3))], vec![], vec![(0, Weighted(4))]]);
+    let overlay = a
+        .overlay(&b)
+        .expect("overlay(&b) resulted in an error unexpectedly");
+    assert_eq!(
+        overlay,
+        Graph::new(vec![
+            vec![(1, Weighted(1)), (2, Weighted(3))],
+            vec![(2, Weighted(2))],
+            vec![(0, Weighted(4))],
+        ])
+    );
+    // Edges shared by endpoints keep the weight from `self`.
+    assert_eq!(
+        a.overlay(&a.map_weights(|w| Weighted(w.0 + 10))),
+        Ok(a.map_weights(|w| *w))
+    );
+    assert_eq!(
+        a.overlay(&Graph::new(vec![vec![], vec![]])),
+        Err(GraphError::NodeCountMismatch {
+            expected: 3,
+            found: 2
+        })
+    );
+}
+
+#[test]
+fn intersection_test() {
+    let a = Graph::new(vec![
+        vec![(1, Weighted(1)), (2, Weighted(5))],
+        vec![(2, Weighted(2))],
+        vec![],
+    ]);
+    let b = Graph::new(vec![
+        vec![(2, Weighted(3)), (2, Weighted(4))],
+        vec![(0, Weighted(1))],
+        vec![(1, Weighted(2))],
+    ]);
+    let common = a
+        .intersection(&b)
+        .expect("intersection(&b) resulted in an error unexpectedly");
+    assert_eq!(common.edges().count(), 1);
+    assert_eq!(
+        common,
+        Graph::new(vec![vec![(2, Unweighted(()))], vec![], vec![]])
+    );
+    assert_eq!(
+        a.intersection(&Graph::default()),
+        Err(GraphError::NodeCountMismatch {
+            expected: 3,
+            found: 0
+        })
+    );
+}
+
+#[test]
+fn edge_difference_test() {
+    let before = &*TEST_GRAPH_WEIGHTED;
+    let mut after = before.map_weights(|w| Weighted(w.0 * 2));
+    after.retain_edges(|u, v, _| (u, v) != (4, 7));
+    after
+        .add_edge(7, 4, Weighted(3))
+        .expect("add_edge(7, 4) resulted in an error unexpectedly");
+    let removed = before
+        .edge_difference(&after)
+        .expect("edge_difference(&after) resulted in an error unexpectedly");
+    assert_eq!(
+        removed.edges().map(|(u, v, _)| (u, v)).collect::<Vec<_>>(),
+        [(4, 7)]
+    );
+    let added = after
+        .edge_difference(before)
+        .expect("edge_difference(before) resulted in an error unexpectedly");
+    assert_eq!(
+        added.edges().map(|(u, v, _)| (u, v)).collect::<Vec<_>>(),
+        [(7, 4)]
+    );
+    assert_eq!(
+        before.edge_difference(before).map(|g| g.edges().count()),
+        Ok(0)
+    );
+    assert_eq!(
+        before.edge_difference(&Graph::default()),
+        Err(GraphError::NodeCountMismatch {
+            expected: 15,
+            found: 0
+        })
+    );
+}
+
+#[test]
+fn remove_node_and_compact_test() {
+    let mut g = TEST_GRAPH_WEIGHTED.map_weights(|w| *w);
+    let mut unchanged = TEST_GRAPH_WEIGHTED.map_weights(|w| *w);
+    assert_eq!(
+        unchanged.compact(&[]),
+        Ok((0..15).map(Some).collect::<Vec<_>>())
+    );
+    assert_eq!(unchanged, *TEST_GRAPH_WEIGHTED);
+    for node in [2, 11, 14] {
+        g.remove_node(node)
+            .expect("remove_node(node) resulted in an error unexpectedly");
+    }
+    // Removed nodes stay in place until the graph is compacted.
+    assert_eq!(g.node_count(), 15);
+    assert_eq!(g.is_isolated(2), Ok(true));
+    let mapping = g
+        .compact(&[2, 11, 14])
+        .expect("compact(&[2, 11, 14]) resulted in an error unexpectedly");
+    let expected: Vec<Option<u32>> = vec![
+        Some(0),
+        Some(1),
+        None,
+        Some(2),
+        Some(3),
+        Some(4),
+        Some(5),
+        Some(6),
+        Some(7),
+        Some(8),
+        Some(9),
+        None,
+        Some(10),
+        Some(11),
+        None,
+    ];
+    assert_eq!(mapping, expected);
+    assert_eq!(g.node_count(), 12);
+    // Every surviving edge is the original one, translated through the mapping.
+    let translated: Vec<(u32, u32, Weighted)> = TEST_GRAPH_WEIGHTED
+        .edges()
+        .filter_map(|(u, v, w)| Some((mapping[u as usize]?, mapping[v as usize]?, *w)))
+        .collect();
+    assert_eq!(
+        g.edges().map(|(u, v, w)| (u, v, *w)).collect::<Vec<_>>(),
+        translated
+    );
+    assert!(matches!(
+        g.remove_node(12),
+        Err(GraphError::OutOfBoundsNode { node: 12 })
+    ));
+    assert_eq!(
+        g.compact(&[12]),
+        Err(GraphError::OutOfBoundsNode { node: 12 })
+    );
+    // Only the listed nodes are dropped: isolated nodes that were never
+    // removed keep a place in the renumbered graph.
+    let mut g: Graph<Unweighted> = Graph::new(vec![vec![], vec![(2, Unweighted(()))], vec![]]);
+    assert_eq!(g.compact(&[2]), Ok(vec![Some(0), Some(1), None]));
+    assert_eq!(g, Graph::new(vec![vec![], vec![]]));
+}
+
+#[test]
+fn sorted_edges_test() {
+    let sorted: Vec<(u32, u32)> = TEST_GRAPH_UNWEIGHTED
+        .sorted_edges()
+        .into_iter()
+        .map(|(u, v, _)| (u, v))
+        .collect();
+    assert_eq!(
+        sorted,
+        [
+            (0, 1),
+            (0, 2),
+            (0, 5),
+            (1, 0),
+            (1, 5),
+            (2, 0),
+            (3, 4),
+            (4, 3),
+            (5, 0)
+        ]
+    );
+    let g = Graph::new(vec![
+        vec![(2, Weighted(1)), (1, Weighted(5)), (1, Weighted(3))],
+        vec![(0, Weighted(2))],
+        vec![],
+    ]);
+    assert_eq!(
+        g.sorted_edges(),
+        [
+            (0, 1, &Weighted(5)),
+            (0, 1, &Weighted(3)),
+            (0, 2, &Weighted(1)),
+            (1, 0, &Weighted(2))
+        ]
+    );
+}
+
+#[test]
+fn component_weights_test() {
+    let undirected = TEST_GRAPH_WEIGHTED
+        .overlay(&TEST_GRAPH_WEIGHTED.transpose())
+        .expect("overlay(&TEST_GRAPH_WEIGHTED.transpose()) resulted in an error unexpectedly");
+    assert!(undirected.is_symmetric());
+    assert_eq!(
+        undirected.component_weights(),
+        vec![((0..10).collect(), 63), ((10..15).collect(), 20)]
+    );
+    let total: u64 = TEST_GRAPH_WEIGHTED
+        .edges()
+        .map(|(_, _, w)| w.0 as u64)
+        .sum();
+    assert_eq!(total, 83);
+    assert_eq!(Graph::<Weighted>::default().component_weights(), vec![]);
+}
+
+#[test]
+fn is_isomorphic_test() {
+    let triangle = Graph::<Unweighted>::cycle(3);
+    let relabeled = triangle
+        .relabel(&[2, 0, 1])
+        .expect("relabel(&[2, 0, 1]) resulted in an error unexpectedly");
+    assert!(triangle.is_isomorphic(&relabeled));
+    let path = Graph::new(vec![
+        vec![(1, Unweighted(()))],
+        vec![(0, Unweighted(())), (2, Unweighted(()))],
+        vec![(1, Unweighted(()))],
+    ]);
+    assert!(!triangle.is_isomorphic(&path));
+    // Directed: a path 0 -> 1 -> 2 matches 2 -> 0 -> 1 but not 0 -> 1 <- 2.
+    let directed = Graph::new(vec![
+        vec![(1, Unweighted(()))],
+        vec![(2, Unweighted(()))],
+        vec![],
+    ]);
+    let shifted = Graph::new(vec![vec![(1, Weighted(5))], vec![], vec![(0, Weighted(1))]]);
+    let converging = Graph::new(vec![
+        vec![(1, Unweighted(()))],
+        vec![],
+        vec![(1, Unweighted(()))],
+    ]);
+    assert!(directed.is_isomorphic(&shifted));
+    assert!(!directed.is_isomorphic(&converging));
+    let perm = [4, 0, 5, 1, 3, 2];
+    let shuffled = TEST_GRAPH_UNWEIGHTED
+        .relabel(&perm)
+        .expect("relabel(&perm) resulted in an error unexpectedly");
+    assert!(TEST_GRAPH_UNWEIGHTED.is_isomorphic(&shuffled));
+    assert!(!TEST_GRAPH_UNWEIGHTED.is_isomorphic(&star_graph(5)));
+}
+
+#[test]
+fn laplacian_test() {
+    let laplacian = TEST_GRAPH_UNWEIGHTED.laplacian();
+    assert_eq!(laplacian.len(), 6);
+    for (u, row) in laplacian.iter().enumerate() {
+        assert_eq!(row.iter().sum::<i64>(), 0, "row {u} does not sum to zero");
+    }
+    let diagonal: Vec<i64> = (0..6).map(|u| laplacian[u][u]).collect();
+    assert_eq!(diagonal, [3, 2, 1, 1, 1, 2]);
+    assert_eq!(laplacian[0][1], -1);
+    assert_eq!(laplacian[1][0], -1);
+    assert_eq!(laplacian[0][3], 0);
+    assert!(Graph::<Unweighted>::default().laplacian().is_empty());
+}
+
+#[test]
+fn adjacency_flat_matches_matrix() {
+    let (flat, n) = TEST_GRAPH_WEIGHTED.adjacency_flat();
+    let matrix = TEST_GRAPH_WEIGHTED.to_adjacency_matrix();
+    assert_eq!(n, 15);
+    assert_eq!(flat.len(), n * n);
+    assert_eq!(matrix.len(), n);
+    for (i, row) in matrix.iter().enumerate() {
+        for (j, &w) in row.iter().enumerate() {
+            assert_eq!(flat[i * n + j], w, "entry ({i}, {j}) differs");
+        }
+    }
+    assert_eq!(matrix[2][5], 8);
+    assert_eq!(matrix[5][2], 0);
+    let parallel = Graph::new(vec![vec![(1, Weighted(4)), (1, Weighted(2))], vec![]]);
+    assert_eq!(parallel.to_adjacency_matrix(), vec![vec![0, 2], vec![0, 0]]);
+    assert_eq!(Graph::<Weighted>::default().adjacency_flat(), (vec![], 0));
+}
+
+#[test]
+fn bfs_iter_matches_bfs() {
+    for source in [0, 2, 10] {
+        let bfs = TEST_GRAPH_WEIGHTED
+            .bfs(source)
+            .expect("bfs(source) resulted in an error unexpectedly");
+        let mut iter = TEST_GRAPH_WEIGHTED
+            .bfs_iter(source)
+            .expect("bfs_iter(source) resulted in an error unexpectedly");
+        let prefix: Vec<u32> = iter.by_ref().take(3).collect();
+        assert_eq!(prefix, bfs[..3]);
+        assert_eq!(prefix.into_iter().chain(iter).collect::<Vec<_>>(), bfs);
+    }
+    // Taking a prefix only discovers the neighbours of the yielded nodes.
+    let mut iter = TEST_GRAPH_WEIGHTED
+        .bfs_iter(0)
+        .expect("bfs_iter(0) resulted in an error unexpectedly");
+    assert_eq!(iter.by_ref().take(2).collect::<Vec<_>>(), [0, 1]);
+    assert_eq!(iter.visited.iter().filter(|&&v| v).count(), 5);
+    assert!(matches!(
+        TEST_GRAPH_WEIGHTED.bfs_iter(15),
+        Err(GraphError::OutOfBoundsNode { node: 15 })
+    ));
+}
+
+#[test]
+fn dfs_iter_matches_dfs() {
+    for source in [0, 2, 10, 14] {
+        let dfs = TEST_GRAPH_WEIGHTED
+            .dfs(source)
+            .expect("dfs(source) resulted in an error unexpectedly");
+        let iter = TEST_GRAPH_WEIGHTED
+            .dfs_iter(source)
+            .expect("dfs_iter(source) resulted in an error unexpectedly");
+        assert_eq!(iter.collect::<Vec<_>>(), dfs);
+    }
+    let mut iter = TEST_GRAPH_UNWEIGHTED
+        .dfs_iter(3)
+        .expect("dfs_iter(3) resulted in an error unexpectedly");
+    assert_eq!(iter.next(), Some(3));
+    assert_eq!(iter.next(), Some(4));
+    assert_eq!(iter.next(), None);
+    assert!(matches!(
+        TEST_GRAPH_UNWEIGHTED.dfs_iter(6),
+        Err(GraphError::OutOfBoundsNode { node: 6 })
+    ));
+}
+
+#[test]
+fn add_edges_is_transactional() {
+    let mut g: Graph<Weighted> = Graph::new(vec![vec![], vec![], vec![]]);
+    g.add_edges(&[(0, 1, Weighted(1)), (1, 2, Weighted(2))])
+        .expect("add_edges(&[(0, 1), (1, 2)]) resulted in an error unexpectedly");
+    let before = g.map_weights(|w| *w);
+    assert_eq!(
+        g.add_edges(&[
+            (2, 0, Weighted(3)),
+            (0, 3, Weighted(4)),
+            (1, 0, Weighted(5))
+        ]),
+        Err(GraphError::OutOfBoundsNode { node: 3 })
+    );
+    assert_eq!(g, before);
+    assert_eq!(
+        g.sorted_edges(),
+        [(0, 1, &Weighted(1)), (1, 2, &Weighted(2))]
+    );
+}
+
+#[test]
+fn add_edge_unique_test() {
+    let mut g: Graph<Weighted> = Graph::new(vec![vec![], vec![]]);
+    assert_eq!(g.add_edge_unique(0, 1, Weighted(3)), Ok(true));
+    assert_eq!(g.add_edge_unique(0, 1, Weighted(5)), Ok(false));
+    assert_eq!(g.add_edge_unique(1, 0, Weighted(5)), Ok(true));
+    assert_eq!(
+        g,
+        Graph::new(vec![vec![(1, Weighted(3))], vec![(0, Weighted(5))]])
+    );
+    assert_eq!(
+        g.add_edge_unique(0, 2, Weighted(1)),
+        Err(GraphError::OutOfBoundsNode { node: 2 })
+    );
+}
+
+#[test]
+fn total_weight_test() {
+    assert_eq!(TEST_GRAPH_WEIGHTED.total_weight(), 83);
+    let undirected = TEST_GRAPH_WEIGHTED
+        .overlay(&TEST_GRAPH_WEIGHTED.transpose())
+        .expect("overlay(&TEST_GRAPH_WEIGHTED.transpose()) resulted in an error unexpectedly");
+    assert_eq!(undirected.total_weight(), 166);
+    assert_eq!(undirected.total_undirected_weight(), 83);
+    let heavy = Graph::new(vec![
+        vec![(1, Weighted(u32::MAX)), (1, Weighted(u32::MAX))],
+        vec![],
+    ]);
+    assert_eq!(heavy.total_weight(), 2 * u32::MAX as u64);
+}
+
+#[test]
+fn reachable_within_matrix_test() {
+    let matrix = TEST_GRAPH_WEIGHTED
+        .reachable_within_matrix(3)
+        .expect("reachable_within_matrix(3) resulted in an error unexpectedly");
+    assert_eq!(matrix.len(), 15);
+    let reachable = |i: usize| -> Vec<u32> { (0..15).filter(|&j| matrix[i][j as usize]).collect() };
+    assert_eq!(reachable(0), [0, 1, 2]);
+    assert_eq!(reachable(2), [1, 2, 3]);
+    assert_eq!(reachable(10), [10, 11]);
+    for (i, row) in matrix.iter().enumerate() {
+        let distances = TEST_GRAPH_WEIGHTED
+            .dijkstra(i as u32)
+            .expect("dijkstra(i) resulted in an error unexpectedly");
+        let expected: Vec<bool> = distances
+            .iter()
+            .map(|d| d.is_some_and(|d| d <= 3))
+            .collect();
+        assert_eq!(*row, expected, "row {i} differs");
+    }
+    // The heavy edge leaves a node within budget, and following it from node 0
+    // overflows, which only makes node 2 unreachable.
+    let heavy = Graph::new(vec![
+        vec![(1, Weighted(1))],
+        vec![(2, Weighted(u32::MAX))],
+        vec![],
+    ]);
+    assert_eq!(
+        heavy.reachable_within_matrix(5),
+        Ok(vec![
+            vec![true, true, false],
+            vec![false, true, false],
+            vec![false, false, true],
+        ])
+    );
+    assert_eq!(
+        heavy.reachable_within_matrix(u32::MAX),
+        Ok(vec![
+            vec![true, true, false],
+            vec![false, true, true],
+            vec![false, false, true],
+        ])
+    );
+}
+
+#[test]
+fn betweenness_centrality_test() {
+    // Every pair of leaves of a star is joined through the centre.
+    assert_eq!(
+        star_graph(4).betweenness_centrality(),
+        [6.0, 0.0, 0.0, 0.0, 0.0]
+    );
+    let exact = TEST_GRAPH_UNWEIGHTED.betweenness_centrality();
+    // Node 0 lies on the only shortest paths 1-0-2 and 2-0-5.
+    assert_eq!(exact, [2.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+    let mut rng = StdRng::seed_from_u64(3);
+    for graph in [&*TEST_GRAPH_UNWEIGHTED, &Graph::grid(3, 4, false)] {
+        let exact = graph.betweenness_centrality();
+        let sampled = graph.betweenness_centrality_sampled(graph.node_count(), &mut rng);
+        assert_eq!(sampled.len(), exact.len());
+        for (s, e) in sampled.iter().zip(&exact) {
+            assert!((s - e).abs() < 1e-9, "{sampled:?} != {exact:?}");
+        }
+    }
+    let estimate = TEST_GRAPH_UNWEIGHTED.betweenness_centrality_sampled(3, &mut rng);
+    assert_eq!(estimate.len(), 6);
+    assert_eq!(
+        TEST_GRAPH_UNWEIGHTED.betweenness_centrality_sampled(0, &mut rng),
+        vec![0.0; 6]
+    );
+}
+
+#[test]
+fn edge_multiplicities_test() {
+    let g = Graph::new(vec![
+        vec![(1, Weighted(1)), (2, Weighted(2)), (1, Weighted(3))],
+        vec![(0, Weighted(1))],
+        vec![],
+    ]);
+    let multiplicities = g.edge_multiplicities();
+    assert_eq!(multiplicities.len(), 3);
+    assert_eq!(multiplicities[&(0, 1)], 2);
+    assert_eq!(multiplicities[&(0, 2)], 1);
+    assert_eq!(multiplicities[&(1, 0)], 1);
+    assert_eq!(multiplicities.get(&(2, 0)), None);
+}
+
+#[test]
+fn unweighted_diameter_test() {
+    for n in 1..7 {
+        let path = Graph::<Unweighted>::grid(1, n, false);
+        assert_eq!(path.unweighted_diameter(), Some(n - 1));
+    }
+    assert_eq!(Graph::<Unweighted>::cycle(7).unweighted_diameter(), Some(3));
+    assert_eq!(
+        Graph::<Unweighted>::grid(3, 4, false).unweighted_diameter(),
+        Some(5)
+    );
+    assert_eq!(star_graph(5).unweighted_diameter(), Some(2));
+    assert_eq!(TEST_GRAPH_UNWEIGHTED.unweighted_diameter(), None);
+    assert_eq!(Graph::<Unweighted>::default().unweighted_diameter(), None);
+}
+
+#[test]
+fn condensation_is_acyclic() {
+    assert!(TEST_GRAPH_WEIGHTED.has_cycle());
+    let (condensed, mapping) = TEST_GRAPH_WEIGHTED.condensation();
+    assert!(!condensed.has_cycle());
+    // 4 -> 7 -> 9 -> 5 -> 4 and 5 -> 8 -> 7 close cycles through 4..=9, and
+    // 10 -> 11 -> 12 -> 13 -> 10 is a cycle of the island.
+    assert_eq!(mapping, [0, 1, 2, 3, 4, 4, 4, 4, 4, 4, 5, 5, 5, 5, 6]);
+    assert_eq!(
+        condensed
+            .sorted_edges()
+            .iter()
+            .map(|&(u, v, _)| (u, v))
+            .collect::<Vec<_>>(),
+        [
+            (0, 1),
+            (0, 2),
+            (1, 3),
+            (1, 4),
+            (2, 1),
+            (2, 3),
+            (2, 4),
+            (3, 4),
+            (6, 5)
+        ]
+    );
+    for (u, v, _) in TEST_GRAPH_WEIGHTED.edges() {
+        let (cu, cv) = (mapping[u as usize], mapping[v as usize]);
+        assert!(cu == cv || condensed[cu].contains(&(cv, Unweighted(()))));
+    }
+    let tree = TEST_GRAPH_UNWEIGHTED
+        .bfs_tree(0)
+        .expect("bfs_tree(0) resulted in an error unexpectedly");
+    let (dag, identity) = tree.condensation();
+    assert!(!dag.has_cycle());
+    assert_eq!(identity, [0, 1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn mst_weight_test() {
+    let mut g: Graph<Weighted> = Graph::new((0..4).map(|_| Vec::new()).collect());
+    for (u, v, w) in [(0, 1, 1), (1, 2, 4), (2, 3, 2), (3, 0, 3), (0, 2, 5)] {
+        g.insert_edge_pub(u, v, Weighted(w), false)
+            .expect("insert_edge_pub(u, v) resulted in an error unexpectedly");
+    }
+    let forest = g.kruskal();
+    assert_eq!(
+        forest,
+        [
+            (0, 1, Weighted(1)),
+            (2, 3, Weighted(2)),
+            (0, 3, Weighted(3))
+        ]
+    );
+    let sum: u64 = forest.iter().map(|(_, _, w)| w.0 as u64).sum();
+    assert_eq!(g.mst_weight(), Ok(sum));
+    assert_eq!(sum, 6);
+    // One tree per component of the sample.
+    assert_eq!(TEST_GRAPH_WEIGHTED.kruskal().len(), 13);
+    assert_eq!(TEST_GRAPH_WEIGHTED.mst_weight(), Ok(15 + 10));
+}
+
+#[test]
+fn vertex_cover_approx_covers_every_edge() {
+    for graph in [
+        &*TEST_GRAPH_UNWEIGHTED,
+        &star_graph(5),
+        &Graph::grid(3, 3, true),
+    ] {
+        let cover = graph.vertex_cover_approx();
+        for (u, v, _) in graph.edges() {
+            assert!(
+                cover.contains(&u) || cover.contains(&v),
+                "edge {u}->{v} is not covered by {cover:?}"
+            );
+        }
+    }
+    assert_eq!(TEST_GRAPH_UNWEIGHTED.vertex_cover_approx(), [0, 1, 3, 4]);
+    // The centre alone covers a star; the approximation takes one leaf too.
+    assert_eq!(star_graph(5).vertex_cover_approx(), [0, 1]);
+    assert!(
+        Graph::<Unweighted>::default()
+            .vertex_cover_approx()
+            .is_empty()
+    );
+}
+
+#[test]
+fn color_with_k_test() {
+    // An even cycle and a grid are bipartite.
+    for graph in [Graph::<Unweighted>::cycle(6), Graph::grid(3, 4, false)] {
+        let coloring = graph
+            .color_with_k(2)
+            .expect("color_with_k(2) resulted in an error unexpectedly");
+        assert!(coloring.iter().all(|&c| c < 2));
+        for (u, v, _) in graph.edges() {
+            assert_ne!(coloring[u as usize], coloring[v as usize]);
+        }
+    }
+    let triangle = Graph::<Unweighted>::cycle(3);
+    assert_eq!(triangle.color_with_k(2), Err(vec![(0, 2)]));
+    assert_eq!(triangle.color_with_k(3), Ok(vec![0, 1, 2]));
+    assert_eq!(triangle.color_with_k(0), Err(vec![(0, 1), (0, 2), (1, 2)]));
+    assert_eq!(Graph::<Unweighted>::default().color_with_k(0), Ok(vec![]));
+}
+
+#[test]
+fn connectivity_index_test() {
+    let mut index = TEST_GRAPH_UNWEIGHTED.connectivity_index();
+    assert!(index.connected(1, 2));
+    assert!(index.connected(5, 0));
+    assert!(index.connected(3, 4));
+    assert!(!index.connected(0, 3));
+    assert!(index.connected(4, 4));
+    // Stream edges into an index of isolated nodes.
+    let mut streamed = Graph::<Unweighted>::new(vec![vec![]; 5]).connectivity_index();
+    assert!(!streamed.connected(0, 4));
+    assert!(streamed.add_edge(0, 1));
+    assert!(streamed.connected(1, 0));
+    assert!(!streamed.connected(0, 2));
+    assert!(streamed.add_edge(3, 2));
+    assert!(!streamed.connected(1, 3));
+    assert!(streamed.add_edge(1, 2));
+    assert!(streamed.connected(0, 3));
+    assert!(!streamed.add_edge(3, 0));
+    assert!(!streamed.connected(0, 4));
+    assert!(!streamed.add_edge(4, 4));
+    assert!(!streamed.connected(3, 4));
+    assert!(index.add_edge(2, 4));
+    assert!(index.connected(0, 3));
+}
+
+#[test]
+fn topological_depth_test() {
+    // 0 -> 1 -> 3 -> 4 and a shortcut 0 -> 4; 2 -> 3.
+    let dag = Graph::new(vec![
+        vec![(1, Unweighted(())), (4, Unweighted(()))],
+        vec![(3, Unweighted(()))],
+        vec![(3, Unweighted(()))],
+        vec![(4, Unweighted(()))],
+        vec![],
+    ]);
+    let depth = dag
+        .topological_depth()
+        .expect("topological_depth() resulted in an error unexpectedly");
+    assert_eq!(depth, [0, 1, 0, 2, 3]);
+    assert_eq!(depth[4], 3);
+    assert_eq!(
+        TEST_GRAPH_WEIGHTED.topological_depth(),
+        Err(GraphError::CyclePresent)
+    );
+    assert_eq!(
+        Graph::<Unweighted>::default().topological_depth(),
+        Ok(vec![])
+    );
+}